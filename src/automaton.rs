@@ -0,0 +1,573 @@
+//! Cellular automata: cells that all change at once, depending on their own state and that of their neighbours.
+//!
+//! [`Automaton`] runs on a fixed size [`Grid`] with any cell state, e.g. the seats of a waiting area.
+//! [`SparseAutomaton`] tracks the active cells of an infinite space with `N` dimensions, e.g. Conway cubes.
+//! [`DenseAutomaton`] runs the same rules on a box around the active cells, which is faster while most of the box is
+//! active or next to an active cell.
+//!
+//! All of them keep a second buffer that the next generation is written into, and compute the offsets or positions of
+//! neighbours once instead of every generation.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::grid::{Grid, NEIGHBOURS_8};
+
+/// Which cells count as neighbours of a cell of an [`Automaton`].
+pub enum Neighbourhood<T> {
+    /// The up to eight orthogonally and diagonally adjacent cells.
+    Adjacent,
+    /// The first cell in each of the eight directions for which the function returns `true`, skipping the cells in
+    /// between. Evaluated on the initial grid, so it should only look at parts of the state that do not change.
+    Visible(fn(&T) -> bool),
+    /// Positions of the neighbours of a cell, evaluated on the initial grid.
+    Custom(CustomNeighbours<T>),
+}
+
+/// Returns the positions of the neighbours of a cell, see [`Neighbourhood::Custom`].
+pub type CustomNeighbours<T> = fn(&Grid<T>, (usize, usize)) -> Vec<(usize, usize)>;
+
+/// A cycle of states, found by [`Automaton::find_cycle`], [`SparseAutomaton::find_cycle`] or
+/// [`DenseAutomaton::find_cycle`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first generation that is part of the cycle.
+    pub start: usize,
+    /// Number of generations until the state repeats, `1` for a state that does not change anymore.
+    pub length: usize,
+}
+
+/* -------------------------------------------------------------------------- */
+
+pub struct Automaton<T> {
+    current: Grid<T>,
+    next: Grid<T>,
+    /// Neighbours of cell `i` are `neighbours[offsets[i]..offsets[i + 1]]`, as indices into the cells.
+    offsets: Vec<usize>,
+    neighbours: Vec<usize>,
+    generation: usize,
+}
+
+/// The states of the neighbours of a cell, passed to the rule of an [`Automaton`].
+pub struct Neighbours<'a, T> {
+    cells: &'a [T],
+    indices: std::slice::Iter<'a, usize>,
+}
+
+impl<'a, T> Iterator for Neighbours<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next().map(|i| &self.cells[*i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Neighbours<'_, T> {}
+
+impl<T: Clone> Automaton<T> {
+    pub fn new(grid: Grid<T>, neighbourhood: Neighbourhood<T>) -> Self {
+        let mut offsets = vec![0];
+        let mut neighbours = vec![];
+        let index = |(x, y): (usize, usize)| y * grid.width() + x;
+
+        for (x, y) in grid.positions() {
+            match &neighbourhood {
+                Neighbourhood::Adjacent => neighbours.extend(grid.neighbours_8(x, y).map(index)),
+                Neighbourhood::Visible(visible) => neighbours.extend(
+                    NEIGHBOURS_8
+                        .into_iter()
+                        .filter_map(|dir| grid.ray(x, y, dir).find(|pos| visible(&grid[*pos])))
+                        .map(index),
+                ),
+                Neighbourhood::Custom(custom) => {
+                    neighbours.extend(custom(&grid, (x, y)).into_iter().map(index));
+                }
+            }
+            offsets.push(neighbours.len());
+        }
+
+        Automaton {
+            next: grid.clone(),
+            current: grid,
+            offsets,
+            neighbours,
+            generation: 0,
+        }
+    }
+}
+
+impl<T> Automaton<T> {
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> Grid<T> {
+        self.current
+    }
+
+    /// Number of steps taken so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Computes the next generation by applying `rule` to every cell and its neighbours. Returns whether any cell
+    /// changed.
+    pub fn step(&mut self, mut rule: impl FnMut(&T, Neighbours<'_, T>) -> T) -> bool
+    where
+        T: PartialEq,
+    {
+        let cells = self.current.as_slice();
+        let mut changed = false;
+
+        for (i, next) in self.next.as_mut_slice().iter_mut().enumerate() {
+            let neighbours = Neighbours {
+                cells,
+                indices: self.neighbours[self.offsets[i]..self.offsets[i + 1]].iter(),
+            };
+            *next = rule(&cells[i], neighbours);
+            changed |= *next != cells[i];
+        }
+
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until no cell changes anymore. Returns the number of generations that changed something.
+    pub fn run_until_stable(&mut self, mut rule: impl FnMut(&T, Neighbours<'_, T>) -> T) -> usize
+    where
+        T: PartialEq,
+    {
+        let start = self.generation;
+        while self.step(&mut rule) {}
+        self.generation - start - 1
+    }
+
+    /// Steps until a state repeats, and stops at the start of the second occurrence.
+    pub fn find_cycle(&mut self, mut rule: impl FnMut(&T, Neighbours<'_, T>) -> T) -> Cycle
+    where
+        T: Clone + Eq + Hash,
+    {
+        let mut seen: HashMap<Vec<T>, usize> = HashMap::new();
+        loop {
+            if let Some(start) = seen.insert(self.current.as_slice().to_vec(), self.generation) {
+                return Cycle {
+                    start,
+                    length: self.generation - start,
+                };
+            }
+            self.step(&mut rule);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Offsets of the `3^N - 1` cells adjacent to a cell in `N` dimensions.
+fn adjacent_offsets<const N: usize>() -> Vec<[i64; N]> {
+    let mut offsets = vec![[0; N]];
+    for dim in 0..N {
+        offsets = offsets
+            .into_iter()
+            .flat_map(|offset| {
+                [-1, 0, 1].map(|d| {
+                    let mut offset = offset;
+                    offset[dim] = d;
+                    offset
+                })
+            })
+            .collect();
+    }
+    offsets.retain(|offset| *offset != [0; N]);
+    offsets
+}
+
+/// The active cells of a two-dimensional grid, placed in the first two of `N` dimensions.
+fn active_cells_of<T, const N: usize>(
+    grid: &Grid<T>,
+    is_active: impl Fn(&T) -> bool,
+) -> Vec<[i64; N]> {
+    assert!(N >= 2, "a grid needs at least two dimensions");
+    grid.enumerate()
+        .filter(|(_, cell)| is_active(cell))
+        .map(|((x, y), _)| {
+            let mut cell = [0; N];
+            cell[0] = x as i64;
+            cell[1] = y as i64;
+            cell
+        })
+        .collect()
+}
+
+/// An automaton on an infinite grid with `N` dimensions, where cells are either active or inactive.
+pub struct SparseAutomaton<const N: usize> {
+    active: HashSet<[i64; N]>,
+    next: HashSet<[i64; N]>,
+    counts: HashMap<[i64; N], usize>,
+    /// Offsets of the `3^N - 1` adjacent cells.
+    offsets: Vec<[i64; N]>,
+    generation: usize,
+}
+
+impl<const N: usize> SparseAutomaton<N> {
+    pub fn new(active: impl IntoIterator<Item = [i64; N]>) -> Self {
+        SparseAutomaton {
+            active: active.into_iter().collect(),
+            next: HashSet::new(),
+            counts: HashMap::new(),
+            offsets: adjacent_offsets(),
+            generation: 0,
+        }
+    }
+
+    /// Places a two-dimensional grid in the first two dimensions, all other coordinates are zero.
+    pub fn from_grid<T>(grid: &Grid<T>, is_active: impl Fn(&T) -> bool) -> Self {
+        Self::new(active_cells_of(grid, is_active))
+    }
+
+    pub fn active(&self) -> &HashSet<[i64; N]> {
+        &self.active
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Computes the next generation. `rule` gets whether a cell is active and how many of its neighbours are, and
+    /// returns whether it is active next. Only cells next to an active one are considered, so inactive cells without
+    /// active neighbours stay inactive. Returns whether any cell changed.
+    pub fn step(&mut self, mut rule: impl FnMut(bool, usize) -> bool) -> bool {
+        self.counts.clear();
+        for cell in &self.active {
+            self.counts.entry(*cell).or_insert(0);
+            for offset in &self.offsets {
+                let neighbour = std::array::from_fn(|i| cell[i] + offset[i]);
+                *self.counts.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+
+        self.next.clear();
+        self.next.extend(
+            self.counts
+                .iter()
+                .filter(|(cell, count)| rule(self.active.contains(*cell), **count))
+                .map(|(cell, _)| *cell),
+        );
+
+        std::mem::swap(&mut self.active, &mut self.next);
+        self.generation += 1;
+        self.active != self.next
+    }
+
+    /// Steps until a set of active cells repeats, and stops at the start of the second occurrence.
+    pub fn find_cycle(&mut self, mut rule: impl FnMut(bool, usize) -> bool) -> Cycle {
+        let mut seen: HashMap<Vec<[i64; N]>, usize> = HashMap::new();
+        loop {
+            let mut state: Vec<[i64; N]> = self.active.iter().copied().collect();
+            state.sort_unstable();
+            if let Some(start) = seen.insert(state, self.generation) {
+                return Cycle {
+                    start,
+                    length: self.generation - start,
+                };
+            }
+            self.step(&mut rule);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Coordinates of the cell at `index` of a box that starts at `origin`, with the first dimension changing fastest.
+fn cell_in_box<const N: usize>(
+    origin: [i64; N],
+    extents: [usize; N],
+    mut index: usize,
+) -> [i64; N] {
+    std::array::from_fn(|dim| {
+        let offset = index % extents[dim];
+        index /= extents[dim];
+        origin[dim] + offset as i64
+    })
+}
+
+/// An automaton on an infinite grid with `N` dimensions, where cells are either active or inactive, stored as a box
+/// that contains all active cells. The box grows by one cell on every side each generation, as far as an active cell
+/// can reach.
+pub struct DenseAutomaton<const N: usize> {
+    /// Whether each cell of the box is active, with the first dimension changing fastest.
+    cells: Vec<bool>,
+    next: Vec<bool>,
+    /// Size of the box in each dimension.
+    extents: [usize; N],
+    /// Coordinates of the first cell of the box.
+    origin: [i64; N],
+    /// Offsets of the `3^N - 1` adjacent cells.
+    offsets: Vec<[i64; N]>,
+    generation: usize,
+}
+
+impl<const N: usize> DenseAutomaton<N> {
+    pub fn new(active: impl IntoIterator<Item = [i64; N]>) -> Self {
+        let active: Vec<[i64; N]> = active.into_iter().collect();
+        let mut origin = [0; N];
+        let mut extents = [0; N];
+        if let Some(first) = active.first() {
+            let (mut min, mut max) = (*first, *first);
+            for cell in &active {
+                for dim in 0..N {
+                    min[dim] = min[dim].min(cell[dim]);
+                    max[dim] = max[dim].max(cell[dim]);
+                }
+            }
+            origin = min;
+            extents = std::array::from_fn(|dim| (max[dim] - min[dim]) as usize + 1);
+        }
+
+        let mut automaton = DenseAutomaton {
+            cells: vec![false; extents.iter().product()],
+            next: vec![],
+            extents,
+            origin,
+            offsets: adjacent_offsets(),
+            generation: 0,
+        };
+        for cell in active {
+            let index = automaton
+                .index(cell)
+                .expect("the box contains all active cells");
+            automaton.cells[index] = true;
+        }
+        automaton
+    }
+
+    /// Places a two-dimensional grid in the first two dimensions, all other coordinates are zero.
+    pub fn from_grid<T>(grid: &Grid<T>, is_active: impl Fn(&T) -> bool) -> Self {
+        Self::new(active_cells_of(grid, is_active))
+    }
+
+    /// Index of `cell` in the box, or `None` outside of it.
+    fn index(&self, cell: [i64; N]) -> Option<usize> {
+        let mut index = 0;
+        for dim in (0..N).rev() {
+            let offset = usize::try_from(cell[dim] - self.origin[dim]).ok()?;
+            if offset >= self.extents[dim] {
+                return None;
+            }
+            index = index * self.extents[dim] + offset;
+        }
+        Some(index)
+    }
+
+    /// Coordinates of the cell at `index` in the box.
+    fn cell(&self, index: usize) -> [i64; N] {
+        cell_in_box(self.origin, self.extents, index)
+    }
+
+    pub fn is_active(&self, cell: [i64; N]) -> bool {
+        self.index(cell).is_some_and(|index| self.cells[index])
+    }
+
+    /// Coordinates of all active cells, with the first dimension changing fastest.
+    pub fn active(&self) -> impl Iterator<Item = [i64; N]> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, active)| **active)
+            .map(|(index, _)| self.cell(index))
+    }
+
+    pub fn count_active(&self) -> usize {
+        self.cells.iter().filter(|active| **active).count()
+    }
+
+    /// Size of the box in each dimension.
+    pub fn extents(&self) -> [usize; N] {
+        self.extents
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Computes the next generation, see [`SparseAutomaton::step`]. Returns whether any cell changed.
+    pub fn step(&mut self, mut rule: impl FnMut(bool, usize) -> bool) -> bool {
+        let extents = self.extents.map(|extent| extent + 2);
+        let origin = self.origin.map(|start| start - 1);
+
+        let mut changed = false;
+        self.next.clear();
+        for index in 0..extents.iter().product() {
+            let cell = cell_in_box(origin, extents, index);
+            let active = self.is_active(cell);
+            let count = self
+                .offsets
+                .iter()
+                .filter(|offset| self.is_active(std::array::from_fn(|dim| cell[dim] + offset[dim])))
+                .count();
+            let next = rule(active, count);
+            changed |= next != active;
+            self.next.push(next);
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.next);
+        self.extents = extents;
+        self.origin = origin;
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until a set of active cells repeats, and stops at the start of the second occurrence.
+    pub fn find_cycle(&mut self, mut rule: impl FnMut(bool, usize) -> bool) -> Cycle {
+        let mut seen: HashMap<Vec<[i64; N]>, usize> = HashMap::new();
+        loop {
+            let mut state: Vec<[i64; N]> = self.active().collect();
+            state.sort_unstable();
+            if let Some(start) = seen.insert(state, self.generation) {
+                return Cycle {
+                    start,
+                    length: self.generation - start,
+                };
+            }
+            self.step(&mut rule);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Automaton, Cycle, DenseAutomaton, Neighbourhood, Neighbours, SparseAutomaton};
+    use crate::grid::Grid;
+
+    fn life(cell: &char, neighbours: Neighbours<'_, char>) -> char {
+        match (cell, neighbours.filter(|n| **n == '#').count()) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn finds_fixed_points() {
+        let block = Grid::parse("....\n.##.\n.##.\n....").unwrap();
+        let mut automaton = Automaton::new(block.clone(), Neighbourhood::Adjacent);
+        assert_eq!(automaton.run_until_stable(life), 0);
+        assert_eq!(automaton.generation(), 1);
+        assert_eq!(automaton.into_grid(), block);
+    }
+
+    #[test]
+    fn finds_cycles() {
+        let blinker = Grid::parse(".....\n..#..\n..#..\n..#..\n.....").unwrap();
+        let mut automaton = Automaton::new(blinker, Neighbourhood::Adjacent);
+        assert!(automaton.step(life));
+        assert_eq!(
+            automaton.grid().to_string(),
+            ".....\n.....\n.###.\n.....\n....."
+        );
+        assert_eq!(
+            automaton.find_cycle(life),
+            Cycle {
+                start: 1,
+                length: 2
+            }
+        );
+    }
+
+    #[test]
+    fn sees_along_rays() {
+        let grid = Grid::parse("#..L\n....\nL..#").unwrap();
+        let count = |neighbourhood| {
+            let mut automaton = Automaton::new(grid.clone(), neighbourhood);
+            automaton.step(|_, neighbours| char::from_digit(neighbours.len() as u32, 10).unwrap());
+            automaton.into_grid().row(0).iter().collect::<String>()
+        };
+
+        assert_eq!(count(Neighbourhood::Adjacent), "3553");
+        assert_eq!(count(Neighbourhood::Visible(|c| *c != '.')), "2332");
+        assert_eq!(
+            count(Neighbourhood::Custom(|grid, (x, y)| grid
+                .neighbours_4(x, y)
+                .collect())),
+            "2332"
+        );
+    }
+
+    #[test]
+    fn runs_conway_cubes() {
+        let grid = Grid::parse(".#.\n..#\n###").unwrap();
+        let rule = |active, count| matches!((active, count), (true, 2 | 3) | (false, 3));
+
+        let mut cubes = SparseAutomaton::<3>::from_grid(&grid, |c| *c == '#');
+        for _ in 0..6 {
+            cubes.step(rule);
+        }
+        assert_eq!(cubes.active().len(), 112);
+
+        let mut hypercubes = SparseAutomaton::<4>::from_grid(&grid, |c| *c == '#');
+        for _ in 0..6 {
+            hypercubes.step(rule);
+        }
+        assert_eq!(hypercubes.active().len(), 848);
+
+        let mut cubes = DenseAutomaton::<3>::from_grid(&grid, |c| *c == '#');
+        for _ in 0..6 {
+            cubes.step(rule);
+        }
+        assert_eq!(cubes.count_active(), 112);
+        assert_eq!(cubes.extents(), [15, 15, 13]);
+
+        let mut hypercubes = DenseAutomaton::<4>::from_grid(&grid, |c| *c == '#');
+        for _ in 0..6 {
+            hypercubes.step(rule);
+        }
+        assert_eq!(hypercubes.count_active(), 848);
+    }
+
+    #[test]
+    fn matches_sparse_automata() {
+        let rule = |active, count| matches!((active, count), (true, 2 | 3) | (false, 3));
+        let glider = [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]];
+        let mut sparse = SparseAutomaton::<2>::new(glider);
+        let mut dense = DenseAutomaton::<2>::new(glider);
+
+        for _ in 0..4 {
+            assert_eq!(sparse.step(rule), dense.step(rule));
+        }
+        let mut active: Vec<_> = dense.active().collect();
+        active.sort_unstable();
+        let mut expected: Vec<_> = sparse.active().iter().copied().collect();
+        expected.sort_unstable();
+        assert_eq!(active, expected);
+        assert!(dense.is_active([2, 3]));
+        assert!(!dense.is_active([100, -100]));
+
+        let mut blinker = DenseAutomaton::<2>::new([[0, 0], [0, 1], [0, 2]]);
+        assert_eq!(
+            blinker.find_cycle(rule),
+            Cycle {
+                start: 0,
+                length: 2
+            }
+        );
+    }
+
+    #[test]
+    fn finds_sparse_cycles() {
+        let mut automaton = SparseAutomaton::<2>::new([[0, 0], [0, 1], [0, 2]]);
+        let cycle = automaton
+            .find_cycle(|active, count| matches!((active, count), (true, 2 | 3) | (false, 3)));
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                length: 2
+            }
+        );
+    }
+}
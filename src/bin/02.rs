@@ -1,95 +1,66 @@
 advent_of_code::solution!(2);
-use anyhow::{anyhow, Error, Ok, Result};
-use nom::{
-    bytes::complete::{tag, take, take_while},
-    IResult, Parser,
-};
+use advent_of_code::parse::{self, ParseError};
 use std::{ops::RangeInclusive, str::FromStr};
 
-fn parse_password_policy(i: &str) -> IResult<&str, PasswordPolicy> {
-    let (i, name) = take_while(|c: char| c.is_numeric())(i)?;
-    let start = name.parse::<u64>().unwrap();
-
-    let (i, _) = tag("-").parse(i)?;
-
-    let (i, name) = take_while(|c: char| c.is_numeric())(i)?;
-    let end = name.parse::<u64>().unwrap();
-
-    let (i, _) = tag(" ").parse(i)?;
-    let (i, target) = take(1usize)(i)?;
+struct PasswordPolicy {
+    pub range: RangeInclusive<u64>,
+    pub target: char,
+}
 
-    let (i, _) = tag(": ")(i)?;
-    IResult::Ok((
-        i,
-        PasswordPolicy {
-            range: RangeInclusive::new(start as usize, end as usize),
-            target: target.bytes().take(1).next().unwrap(),
-        },
-    ))
+/// A line of the input, e.g. `1-3 a: abcde`.
+struct Entry {
+    policy: PasswordPolicy,
+    password: String,
 }
 
-struct PasswordPolicy {
-    pub range: RangeInclusive<usize>,
-    pub target: u8,
+impl FromStr for Entry {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (policy, password) = s
+            .split_once(": ")
+            .ok_or_else(|| ParseError::new("expected `policy: password`", s))?;
+        let (range, target) = parse::range_char(policy)?;
+
+        Ok(Entry {
+            policy: PasswordPolicy { range, target },
+            password: password.to_string(),
+        })
+    }
 }
 
 impl PasswordPolicy {
     pub fn is_valid(&self, password: &str) -> bool {
         self.range
-            .contains(&password.bytes().filter(|c| *c == self.target).count())
+            .contains(&(password.chars().filter(|c| *c == self.target).count() as u64))
+    }
+
+    /// Exactly one of the two (1-based) positions holds the target.
+    pub fn is_valid_2(&self, password: &str) -> bool {
+        let at = |pos: u64| {
+            pos.checked_sub(1)
+                .and_then(|i| password.chars().nth(i as usize))
+                == Some(self.target)
+        };
+        at(*self.range.start()) != at(*self.range.end())
     }
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let count = input
-        .lines()
-        .map(parse_password_policy)
-        .filter_map(Result::ok)
-        .filter(|(password, policy)| policy.is_valid(password))
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let count = parse::lines::<Entry>(input)?
+        .into_iter()
+        .filter(|entry| entry.policy.is_valid(&entry.password))
         .count();
 
-    Some(count as u64)
+    Ok(count as u64)
 }
 
-struct Password2;
-
-impl FromStr for Password2 {
-    type Err = Error;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let res = s.split(char::is_whitespace).take(3).collect::<Vec<_>>();
-        let (lower, upper) = res[0].split_once('-').ok_or(anyhow!("cannot split"))?;
-        let target = res[1].chars().take(1).next().ok_or(anyhow!("nope"))?;
-        let lower = lower.parse::<usize>()?;
-        let upper = upper.parse::<usize>()?;
-
-        let mut chars = res[2].chars();
-
-        let c1 = chars
-            .by_ref()
-            .skip(lower - 1)
-            .next()
-            .ok_or(anyhow!("invalid pos"))?;
-
-        let c2 = chars
-            .skip(upper - lower - 1)
-            .next()
-            .ok_or(anyhow!("invalid pos"))?;
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let count = parse::lines::<Entry>(input)?
+        .into_iter()
+        .filter(|entry| entry.policy.is_valid_2(&entry.password))
+        .count();
 
-        if (c1 == target && c2 == target) || (c1 != target && c2 != target) {
-            Err(anyhow!("invalid occurence count"))
-        } else {
-            Ok(Password2)
-        }
-    }
-}
-pub fn part_two(input: &str) -> Option<u64> {
-    Some(
-        input
-            .lines()
-            .map(str::parse::<Password2>)
-            .filter(Result::is_ok)
-            .count() as u64,
-    )
+    Ok(count as u64)
 }
 
 #[cfg(test)]
@@ -99,12 +70,21 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(2));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(1));
+    }
+
+    #[test]
+    fn test_invalid_entry() {
+        let result = part_one("1-3 a: abcde\n1-3 b cdefg\n");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 2, column 1: expected `policy: password`"
+        );
     }
 }
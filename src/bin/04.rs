@@ -1,12 +1,12 @@
-use std::collections::HashMap;
-
 advent_of_code::solution!(4);
 
 #[derive(Debug)]
 struct Passport<'a> {
-    pub contents: Vec<&'a str>,
+    pub fields: Vec<(&'a str, &'a str)>,
 }
 
+const REQUIRED_FIELDS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
 impl<'a> Passport<'a> {
     //  `byr` (birth year)
     //  `iyr` (issue year)
@@ -16,45 +16,24 @@ impl<'a> Passport<'a> {
     //  `ecl` (eye color)
     //  `pid` (passport id)
     fn is_valid(&self) -> bool {
-        let mut map = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
-            .into_iter()
-            .map(|v| (v, false))
-            .collect::<HashMap<_, _>>();
-
-        self.contents.iter().for_each(|name| {
-            if let Some((title, _)) = name.split_once(':') {
-                let e = map.entry(title).or_default();
-                *e = true;
-            }
-        });
-
-        map.values().all(|v| *v)
+        REQUIRED_FIELDS
+            .iter()
+            .all(|required| self.fields.iter().any(|(key, _)| key == required))
     }
 }
 
-fn get_passport<'a>(iter: &mut impl Iterator<Item = &'a str>) -> Option<Passport<'a>> {
-    let mut contents = vec![];
-    for line in iter {
-        if line.trim().is_empty() {
-            return Some(Passport { contents });
-        }
-        contents.extend(line.split_ascii_whitespace());
-    }
-
-    if contents.is_empty() {
-        None
-    } else {
-        Some(Passport { contents })
-    }
+fn get_passports(input: &str) -> Result<Vec<Passport<'_>>, ParseError> {
+    parse::groups(input)
+        .map(|group| {
+            let fields = parse::records(group).map_err(|e| e.locate(input))?;
+            Ok(Passport { fields })
+        })
+        .collect()
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let mut lines = input.lines();
-    let mut passports = vec![];
-    while let Some(passport) = get_passport(&mut lines) {
-        passports.push(passport);
-    }
-    Some(passports.into_iter().filter(|p| p.is_valid()).count() as u64)
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let passports = get_passports(input)?;
+    Ok(passports.into_iter().filter(|p| p.is_valid()).count() as u64)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -69,30 +48,29 @@ enum PassportField {
     CID,
 }
 
-use anyhow::{anyhow, Error, Ok};
-use std::str::FromStr;
+use advent_of_code::parse::{self, ParseError};
+use anyhow::{anyhow, Error};
 
-impl FromStr for PassportField {
-    type Err = Error;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (k, v) = s.split_once(':').ok_or(anyhow!("not enough"))?;
+impl TryFrom<(&str, &str)> for PassportField {
+    type Error = Error;
+    fn try_from((k, v): (&str, &str)) -> Result<Self, Self::Error> {
         match k {
             "byr" => {
-                let value = v.parse::<u32>()?;
+                let value = parse::unsigned(v)?;
                 if !(1920..=2002).contains(&value) {
                     return Err(anyhow!("invalid byr"));
                 }
                 Ok(PassportField::BYR)
             }
             "iyr" => {
-                let value = v.parse::<u32>()?;
+                let value = parse::unsigned(v)?;
                 if !(2010..=2020).contains(&value) {
                     return Err(anyhow!("invalid iyr"));
                 }
                 Ok(PassportField::IYR)
             }
             "eyr" => {
-                let value = v.parse::<u32>()?;
+                let value = parse::unsigned(v)?;
                 if !(2020..=2030).contains(&value) {
                     return Err(anyhow!("invalid eyr"));
                 }
@@ -177,34 +155,17 @@ impl FromStr for PassportField {
 
 impl<'a> Passport<'a> {
     fn is_valid_2(&self) -> bool {
-        let mut map = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"]
-            .into_iter()
-            .map(|v| (v, None))
-            .collect::<HashMap<_, _>>();
-
-        self.contents.iter().for_each(|name| {
-            if let Some((title, _)) = name.split_once(':') {
-                let e = map.entry(title).or_default();
-                *e = name.parse::<PassportField>().ok();
-            }
-        });
-
-        if !map.values().all(|v| v.is_some()) {
-            return false;
-        }
-
-        true
+        REQUIRED_FIELDS.iter().all(|required| {
+            self.fields.iter().any(|(key, value)| {
+                key == required && PassportField::try_from((*key, *value)).is_ok()
+            })
+        })
     }
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let mut lines = input.lines();
-    let mut passports = vec![];
-    while let Some(passport) = get_passport(lines.by_ref()) {
-        passports.push(passport);
-    }
-
-    Some(passports.into_iter().filter(|p| p.is_valid_2()).count() as u64)
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let passports = get_passports(input)?;
+    Ok(passports.into_iter().filter(|p| p.is_valid_2()).count() as u64)
 }
 
 // * `byr` (Birth Year) - four digits; at least `1920` and at most `2002`.
@@ -287,12 +248,21 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(2));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn test_invalid_field() {
+        let result = part_one("ecl:gry pid:860033327\n\niyr:2013 hgt\n");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 3, column 10: expected `key:value`, found `hgt`"
+        );
     }
 }
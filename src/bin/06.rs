@@ -16,11 +16,11 @@ advent_of_code::solution!(6);
 //
 // b
 //
+use advent_of_code::parse;
 use std::collections::HashSet;
 pub fn part_one(input: &str) -> Option<u64> {
     Some(
-        input
-            .split("\n\n")
+        parse::groups(input)
             .map(|group| {
                 let mut set = HashSet::new();
                 group.chars().for_each(|c| match c {
@@ -37,8 +37,7 @@ pub fn part_one(input: &str) -> Option<u64> {
 
 pub fn part_two(input: &str) -> Option<u64> {
     Some(
-        input
-            .split("\n\n")
+        parse::groups(input)
             .map(|group| {
                 let mut counts = vec![0; 26];
                 let mut len = 0;
@@ -61,12 +60,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(11));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(6));
     }
 }
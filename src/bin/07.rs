@@ -10,8 +10,11 @@ advent_of_code::solution!(7);
 // faded blue bags contain no other bags.
 // dotted black bags contain no other bags.
 
-use std::collections::HashMap;
-use std::collections::VecDeque;
+use std::str::FromStr;
+
+use advent_of_code::graph::Graph;
+use advent_of_code::parse::{self, ParseError};
+use anyhow::anyhow;
 
 #[derive(Debug, Clone)]
 struct Bag {
@@ -20,97 +23,82 @@ struct Bag {
 }
 
 impl Bag {
-    pub fn containable(&self, color: &str) -> bool {
-        self.contains
-            .iter()
-            .any(|(bag_color, _)| *bag_color == color)
-    }
-
     pub fn new(color: String, contains: Vec<(String, usize)>) -> Bag {
         Bag { color, contains }
     }
 }
 
-peg::parser! {
-    grammar bag_parser() for str {
-        pub rule bag() -> Bag
-        = b: empty_bag() / b: stuffed_bag() { b }
-        // = b: stuffed_bag() / b: empty_bag() { b }
-
-        pub rule stuffed_bag() -> Bag
-        = c:color() whitespaces() "bags contain" whitespaces() bags: contained_bags() "."? { Bag::new(c.to_owned(), bags)}
-
-        rule empty_bag() -> Bag
-        = c:color() whitespaces() "bags contain no other bags." { Bag::new(c.to_owned(), vec![])}
-
-        rule color() -> String
-        = c:$((['a'..='z' | 'A'..='Z']*)** <2,2> whitespaces()) { c.to_owned() }
-        // = c:$(['a'..='z' | 'A'..='Z']* whitespaces() ['a'..='z' | 'A'..='Z']*) { dbg!(c).to_owned() }
-
-        rule whitespaces()
-        = [ c if c.is_whitespace() ] *
-
-        rule count() -> usize
-        = num:$(['0'..='9']*) {? num.parse::<usize>().or(Err("usize")) }
-
-        rule contained_bags() -> Vec<(String, usize)>
-        = bags: (single_bag() ** ", " ) { bags }
-
-        rule single_bag() -> (String, usize)
-        = count:count() whitespaces() color:color() whitespaces() "bag" "s"? { (color, count) }
+impl FromStr for Bag {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (color, contents) = s.split_once(" bags contain ").ok_or_else(|| {
+            ParseError::new(
+                format!("expected `<color> bags contain ...`, found `{s}`"),
+                s,
+            )
+        })?;
+        let contents = contents.strip_suffix('.').unwrap_or(contents);
+
+        let contains = match contents {
+            "no other bags" => vec![],
+            _ => contents
+                .split(", ")
+                .map(parse_contained_bag)
+                .collect::<Result<_, _>>()?,
+        };
+        Ok(Bag::new(color.to_owned(), contains))
     }
 }
 
-// impl<'input> FromStr for Bag<'input> {
-//     type Err = anyhow::Error;
-//     fn from_str(s: &str) -> Result<Self, Self::Err> {}
-// }
-
-use std::collections::HashSet;
-pub fn part_one(input: &str) -> Option<u64> {
-    let bags = input
-        .lines()
-        .map(|l| bag_parser::bag(l).unwrap())
-        .collect::<Vec<_>>();
-
-    let mut usable_bags = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back("shiny gold");
-
-    while let Some(target) = queue.pop_front() {
-        for bag in &bags {
-            if !usable_bags.contains(&bag.color) && bag.containable(target) {
-                usable_bags.insert(bag.color.clone());
-                queue.push_back(&bag.color);
-            }
-        }
-    }
-
-    Some(usable_bags.len() as u64)
+/// The color and count of an entry like `2 muted yellow bags`.
+fn parse_contained_bag(s: &str) -> Result<(String, usize), ParseError> {
+    let (count, rest) = s.split_once(' ').ok_or_else(|| {
+        ParseError::new(format!("expected `<count> <color> bags`, found `{s}`"), s)
+    })?;
+    let color = rest
+        .strip_suffix(" bags")
+        .or_else(|| rest.strip_suffix(" bag"))
+        .ok_or_else(|| ParseError::new(format!("expected `<color> bags`, found `{rest}`"), rest))?;
+    Ok((color.to_owned(), parse::unsigned(count)? as usize))
 }
 
-fn count_bag(bags: &HashMap<String, Bag>, target: &str) -> usize {
-    if let Some(target_bag) = bags.get(target) {
-        let mut ret = 1;
-        for (next_bag, count) in &target_bag.contains {
-            ret += count * count_bag(bags, next_bag);
+/// Every bag points to the bags it directly contains, weighted by how many of them.
+fn parse_bags(input: &str) -> Result<Graph<String, usize>, ParseError> {
+    let mut graph = Graph::new();
+    for bag in parse::lines::<Bag>(input)? {
+        let outer = graph.node(bag.color);
+        for (color, count) in bag.contains {
+            let inner = graph.node(color);
+            graph.add_edge_ids(outer, inner, count);
         }
-        ret
-    } else {
-        0
     }
+    Ok(graph)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let bags = input
-        .lines()
-        .map(|l| {
-            let bag = bag_parser::bag(l).unwrap();
-            (bag.color.clone(), bag)
-        })
-        .collect::<HashMap<_, _>>();
+pub fn part_one(input: &str) -> anyhow::Result<u64> {
+    let bags = parse_bags(input)?;
+    let Some(target) = bags.id("shiny gold") else {
+        return Ok(0);
+    };
+
+    // NOTE: the reachable nodes include the shiny gold bag itself.
+    Ok(bags.reversed().bfs(target).len() as u64 - 1)
+}
 
-    Some(count_bag(&bags, "shiny gold") as u64 - 1)
+pub fn part_two(input: &str) -> anyhow::Result<u64> {
+    let bags = parse_bags(input)?;
+    let Some(target) = bags.id("shiny gold") else {
+        return Ok(0);
+    };
+
+    let inside = bags
+        .accumulate(target, |_, contains| {
+            contains
+                .map(|(count, inside)| count * (1 + inside))
+                .sum::<usize>()
+        })
+        .ok_or_else(|| anyhow!("the shiny gold bag contains itself"))?;
+    Ok(inside as u64)
 }
 
 #[cfg(test)]
@@ -120,12 +108,32 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result.unwrap(), 4);
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result.unwrap(), 32);
+    }
+
+    #[test]
+    fn test_invalid_bags() {
+        let result = part_one(
+            "shiny gold bags contain no other bags.\nfaded blue bags contain two shiny gold bags.",
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 2, column 25: expected an unsigned integer, found `two`"
+        );
+    }
+
+    #[test]
+    fn test_cyclic_bags() {
+        let result = part_two("shiny gold bags contain 2 shiny gold bags.");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "the shiny gold bag contains itself"
+        );
     }
 }
@@ -1,3 +1,7 @@
+use advent_of_code::console::{Console, Instruction, Op, Registers};
+use advent_of_code::graph::Graph;
+use anyhow::anyhow;
+
 advent_of_code::solution!(8);
 
 // nop +0
@@ -10,150 +14,67 @@ advent_of_code::solution!(8);
 // jmp -4
 // acc +6
 
-#[derive(Debug, Copy, Clone)]
-enum Op {
-    Nop(i32),
-    Acc(i32),
-    Jmp(i32),
+pub fn part_one(input: &str) -> anyhow::Result<u64> {
+    let mut console = Console::<Op>::parse(input)?;
+    console.run_until_loop();
+    Ok(console.registers().acc as u64)
 }
 
-#[derive(Debug, Clone)]
-struct Machine {
-    pub ops: Vec<(Op, bool)>,
-    pub acc: i32,
-    pub pc: i32,
+/// Address of the instruction after `op` at `pc`, following the jump of a `jmp`.
+fn next(pc: usize, op: &Op) -> i64 {
+    let mut registers = Registers {
+        pc: pc as i64,
+        acc: 0,
+    };
+    op.execute(&mut registers);
+    registers.pc
 }
 
-impl Machine {
-    fn run(&mut self) -> u64 {
-        loop {
-            let (op, runned) = &mut self.ops[usize::try_from(self.pc).unwrap()];
-            if *runned {
-                break;
-            }
-
-            *runned = true;
-            match op {
-                Op::Acc(operand) => self.acc += *operand,
-                Op::Jmp(operand) => {
-                    self.pc += *operand;
-                    continue;
-                }
-                _ => {}
-            }
-            self.pc += 1;
-        }
-        self.acc as u64
+/// Finds the `jmp` or `nop` to flip in linear time: every address that leads to the end of the program is found by
+/// walking the edges backwards from it, then the original program is run until it reaches an instruction that jumps
+/// into that set once flipped.
+fn repair(program: &[Op]) -> Option<usize> {
+    // NOTE: address `program.len()` is the end of the program, and every address is its own node id.
+    let mut flow: Graph<usize> = Graph::new();
+    for pc in 0..=program.len() {
+        flow.node(pc);
     }
-
-    fn try_run(&mut self) -> Option<u64> {
-        self.ops
-            .iter_mut()
-            .for_each(|(_, visited)| *visited = false);
-        self.pc = 0;
-        self.acc = 0;
-        loop {
-            if self.pc >= self.ops.len() as i32 {
-                return Some(self.acc as u64);
-            }
-
-            let (op, runned) = &mut self.ops[usize::try_from(self.pc).unwrap()];
-            if *runned {
-                return None;
-            }
-
-            *runned = true;
-            match op {
-                Op::Acc(operand) => self.acc += *operand,
-                Op::Jmp(operand) => {
-                    self.pc += *operand;
-                    continue;
-                }
-                _ => {}
-            }
-            self.pc += 1;
+    for (pc, op) in program.iter().enumerate() {
+        let to = next(pc, op);
+        if (0..=program.len() as i64).contains(&to) {
+            flow.add_edge_ids(pc, to as usize, ());
         }
     }
 
-    fn swap_op(&mut self, index: usize) -> Option<Op> {
-        match self.ops[index].0 {
-            Op::Nop(operand) => Some(Op::Jmp(operand)),
-            Op::Jmp(operand) => Some(Op::Nop(operand)),
-            _ => None,
-        }
+    let mut halts = vec![false; program.len() + 1];
+    for pc in flow.reversed().bfs(program.len()) {
+        halts[pc] = true;
     }
 
-    fn find_it(&mut self) -> u64 {
-        for i in 0..self.ops.len() {
-            if let Some(replacement) = self.swap_op(i) {
-                let origin = self.ops[i].0;
-                self.ops[i].0 = replacement;
-                if let Some(res) = self.try_run() {
-                    return res;
-                }
-                self.ops[i].0 = origin;
+    let mut executed = vec![false; program.len()];
+    let mut pc = 0;
+    while pc < program.len() && !std::mem::replace(&mut executed[pc], true) {
+        if let Some(flipped) = program[pc].flipped() {
+            let to = next(pc, &flipped);
+            if usize::try_from(to).is_ok_and(|to| halts.get(to) == Some(&true)) {
+                return Some(pc);
             }
         }
-        unreachable!()
+        pc = usize::try_from(next(pc, &program[pc])).ok()?;
     }
+    None
 }
 
-peg::parser! {
-    grammar op_parser() for str {
-        pub rule op() -> crate::Op
-        = code:code() whitespaces() count:count() {
-            match code {
-                "acc" => crate::Op::Acc(count),
-                "nop" => crate::Op::Nop(count),
-                "jmp" => crate::Op::Jmp(count),
-                _ => todo!(),
-            }
-        }
-
-        rule code() -> &'input str
-        = code:$("acc" / "nop" / "jmp") { code }
-
-        rule whitespaces()
-        = ['\n' | '\t' | '\r' | ' ']*
-
-        rule count() -> i32
-        = mark:$(['+' | '-']) num:$(['0'..='9']*) {
-            let neg = mark.starts_with('-');
-            let ret = num.parse::<i32>().unwrap();
-            if neg {
-                -ret
-            } else {
-                ret
-            }
-        }
-    }
+pub fn part_two(input: &str) -> anyhow::Result<u64> {
+    let mut console = Console::<Op>::parse(input)?;
+    let address = repair(console.program()).ok_or_else(|| {
+        anyhow!("no single `jmp` or `nop` can be flipped to make the program halt")
+    })?;
 
-}
-pub fn part_one(input: &str) -> Option<u64> {
-    let ops = input
-        .lines()
-        .map(|line| {
-            let op = op_parser::op(line).unwrap();
-            (op, false)
-        })
-        .collect::<Vec<_>>();
-
-    let mut machine = Machine { ops, acc: 0, pc: 0 };
-    Some(machine.run())
-}
-
-pub fn part_two(input: &str) -> Option<u64> {
-    let ops = input
-        .lines()
-        .map(|line| {
-            let op = op_parser::op(line).unwrap();
-            (op, false)
-        })
-        .collect::<Vec<_>>();
-
-    let mut machine = Machine { ops, acc: 0, pc: 0 };
-
-    Some(machine.find_it())
+    let program = console.program_mut();
+    program[address] = program[address].flipped().unwrap();
+    console.run_until_loop();
+    Ok(console.registers().acc as u64)
 }
 
 #[cfg(test)]
@@ -163,12 +84,39 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result.unwrap(), 5);
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result.unwrap(), 8);
+    }
+
+    #[test]
+    fn test_repair_is_linear() {
+        // every `nop` is a candidate that loops right away once flipped to `jmp +0`, and only flipping the final jump
+        // halts. Brute force re-runs the program up to each candidate, which is quadratic in its length.
+        let pairs = 100_000;
+        let program = format!("{}jmp -{}\n", "nop +0\nacc +1\n".repeat(pairs), 2 * pairs);
+        assert_eq!(part_two(&program).unwrap(), pairs as u64);
+    }
+
+    #[test]
+    fn test_unrepairable_program() {
+        let result = part_two("acc +1\njmp -1\nacc +2\njmp -1");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "no single `jmp` or `nop` can be flipped to make the program halt"
+        );
+    }
+
+    #[test]
+    fn test_invalid_op() {
+        let result = part_one("nop +0\nmul +3\n");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "line 2, column 1: expected one of \"acc\", \"jmp\", \"nop\""
+        );
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use advent_of_code::graph::Graph;
 
 advent_of_code::solution!(10);
 
@@ -26,26 +26,6 @@ pub fn part_one(input: &str) -> Option<u64> {
     Some(counts.0 * counts.1)
 }
 
-fn get_path_count(curr: u64, slice: &[u64], map: &mut HashMap<u64, u64>) -> u64 {
-    if slice.len() <= 1 {
-        return 1;
-    }
-
-    if map.contains_key(&curr) {
-        return *map.get(&curr).unwrap();
-    }
-
-    let count = slice
-        .iter()
-        .enumerate()
-        .take_while(|(_, v)| curr + 3 >= **v && **v > curr)
-        .fold(0, |count, (idx, v)| {
-            count + get_path_count(*v, &slice[idx + 1..], map)
-        });
-
-    map.insert(curr, count);
-    count
-}
 pub fn part_two(input: &str) -> Option<u64> {
     let mut nums = input
         .lines()
@@ -53,8 +33,18 @@ pub fn part_two(input: &str) -> Option<u64> {
         .collect::<Vec<_>>();
     nums.push(0);
     nums.sort();
-    let mut map = HashMap::new();
-    Some(get_path_count(nums[0], &nums[1..], &mut map))
+
+    // Every adapter points to the adapters that can be plugged into it.
+    let mut adapters: Graph<u64> = Graph::new();
+    for (i, from) in nums.iter().enumerate() {
+        let from = adapters.node(*from);
+        for to in nums[i + 1..].iter().take_while(|to| **to <= nums[i] + 3) {
+            let to = adapters.node(*to);
+            adapters.add_edge_ids(from, to, ());
+        }
+    }
+
+    adapters.count_paths(adapters.id(&0)?, adapters.id(nums.last()?)?)
 }
 
 #[cfg(test)]
@@ -64,12 +54,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(35));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(8));
     }
 }
@@ -1,4 +1,5 @@
-use itertools::Itertools;
+use advent_of_code::automaton::{Automaton, Neighbourhood, Neighbours};
+use advent_of_code::grid::Grid;
 
 advent_of_code::solution!(11);
 
@@ -13,155 +14,41 @@ advent_of_code::solution!(11);
 // #.LLLLLL.L
 // #.#LLLL.##
 
-fn surrounding_occupied_seats(pos: (usize, usize), map: &[Vec<u8>]) -> usize {
-    let dirs = [
-        (-1, -1),
-        (1, 1),
-        (-1, 1),
-        (1, -1),
-        (0, 1),
-        (1, 0),
-        (0, -1),
-        (-1, 0),
-    ];
-
-    dirs.into_iter()
-        .filter_map(|(r, c)| {
-            let next_r = r + pos.0 as i64;
-            let next_c = c + pos.1 as i64;
-            if next_r >= 0
-                && next_r < map.len() as i64
-                && next_c >= 0
-                && next_c < map[0].len() as i64
-            {
-                Some((next_r as usize, next_c as usize))
-            } else {
-                None
-            }
-        })
-        .fold(0, |count, pos| {
-            if map[pos.0][pos.1] == b'#' {
-                count + 1
-            } else {
-                count
-            }
-        })
+#[cfg(feature = "visualize")]
+fn seat_color(seat: char) -> advent_of_code::visualize::Color {
+    use advent_of_code::visualize::Color;
+    match seat {
+        '#' => Color::YELLOW,
+        'L' => Color::GRAY,
+        _ => Color::BLACK,
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let mut prev_map = input.lines().map(|l| l.as_bytes().to_vec()).collect_vec();
-    let mut map = prev_map.clone();
+/// Applies the seating rules until nothing changes and counts the occupied seats.
+fn settle(input: &str, neighbourhood: Neighbourhood<char>, tolerance: usize) -> Option<u64> {
+    let mut seats = Automaton::new(Grid::parse(input).ok()?, neighbourhood);
 
-    let mut muted = true;
-    let rlen = map.len();
-    let clen = map[0].len();
+    let rule = |seat: &char, mut neighbours: Neighbours<'_, char>| match seat {
+        'L' if neighbours.all(|n| *n != '#') => '#',
+        '#' if neighbours.filter(|n| **n == '#').count() >= tolerance => 'L',
+        seat => *seat,
+    };
 
-    while muted {
-        muted = false;
-        (0..rlen)
-            .flat_map(|r| std::iter::repeat(r).zip(0..clen))
-            .for_each(
-                |(r, c)| match surrounding_occupied_seats((r, c), &prev_map) {
-                    0 if map[r][c] == b'L' => {
-                        muted = true;
-                        map[r][c] = b'#';
-                    }
-                    4.. if map[r][c] == b'#' => {
-                        muted = true;
-                        map[r][c] = b'L';
-                    }
-                    _ => {}
-                },
-            );
-        prev_map = map.clone();
+    while seats.step(rule) {
+        advent_of_code::visualize!(grid(seats.grid().width(), seats.grid().height(), |x, y| {
+            seat_color(seats.grid()[(x, y)])
+        }));
     }
 
-    Some(map.into_iter().flat_map(|v| v.into_iter()).fold(
-        0,
-        |sum, c| {
-            if c == b'#' {
-                sum + 1
-            } else {
-                sum
-            }
-        },
-    ))
+    Some(seats.grid().iter().filter(|seat| **seat == '#').count() as u64)
 }
 
-fn occupied_seats(pos: (usize, usize), map: &[Vec<u8>]) -> usize {
-    let dirs = [
-        (-1, -1),
-        (1, 1),
-        (-1, 1),
-        (1, -1),
-        (0, 1),
-        (1, 0),
-        (0, -1),
-        (-1, 0),
-    ];
-
-    dirs.into_iter()
-        .filter_map(|(r, c)| {
-            let mut next_r = r + pos.0 as i64;
-            let mut next_c = c + pos.1 as i64;
-            while next_r >= 0
-                && next_r < map.len() as i64
-                && next_c >= 0
-                && next_c < map[0].len() as i64
-            {
-                if map[next_r as usize][next_c as usize] != b'.' {
-                    return Some((next_r as usize, next_c as usize));
-                }
-                next_r += r;
-                next_c += c;
-            }
-            None
-        })
-        .fold(0, |count, pos| {
-            if map[pos.0][pos.1] == b'#' {
-                count + 1
-            } else {
-                count
-            }
-        })
+pub fn part_one(input: &str) -> Option<u64> {
+    settle(input, Neighbourhood::Adjacent, 4)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let mut prev_map = input.lines().map(|l| l.as_bytes().to_vec()).collect_vec();
-    let mut map = prev_map.clone();
-
-    let mut muted = true;
-    let rlen = map.len();
-    let clen = map[0].len();
-
-    while muted {
-        muted = false;
-        (0..rlen)
-            .flat_map(|r| std::iter::repeat(r).zip(0..clen))
-            .for_each(|(r, c)| match occupied_seats((r, c), &prev_map) {
-                0 if map[r][c] == b'L' => {
-                    muted = true;
-                    map[r][c] = b'#';
-                }
-                5.. if map[r][c] == b'#' => {
-                    muted = true;
-                    map[r][c] = b'L';
-                }
-                _ => {}
-            });
-        prev_map = map.clone();
-    }
-
-    Some(map.into_iter().flat_map(|v| v.into_iter()).fold(
-        0,
-        |sum, c| {
-            if c == b'#' {
-                sum + 1
-            } else {
-                sum
-            }
-        },
-    ))
+    settle(input, Neighbourhood::Visible(|cell| *cell != '.'), 5)
 }
 
 #[cfg(test)]
@@ -171,12 +58,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(37));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Some(26));
     }
 }
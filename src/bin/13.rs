@@ -1,125 +1,53 @@
-advent_of_code::solution!(13);
+use advent_of_code::number_theory::{crt, Congruence};
+use advent_of_code::parse::{self, ParseError};
+use anyhow::anyhow;
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let mut lines = input.lines();
-    let arrived_time = lines
-        .by_ref()
-        .take(1)
-        .next()
-        .unwrap()
-        .parse::<u64>()
-        .unwrap();
+advent_of_code::solution!(13);
 
-    let buses = lines
-        .take(1)
-        .flat_map(|l| l.split(','))
-        .filter_map(|x| {
-            if x.chars().take(1).next().unwrap() == 'x' {
-                None
-            } else {
-                Some(x.parse::<u64>().unwrap())
-            }
+/// The ids of the buses in service with their offset in the list, `x` marks buses that are out of service.
+fn parse_buses(line: &str) -> Result<Vec<(u64, u64)>, ParseError> {
+    line.split(',')
+        .enumerate()
+        .filter(|(_, id)| id.trim() != "x")
+        .map(|(idx, id)| match parse::unsigned(id)? {
+            0 => Err(ParseError::new("bus ids start at 1, found `0`", id)),
+            bus => Ok((bus, idx as u64)),
         })
-        .collect::<Vec<_>>();
-
-    if let Some((id, time)) = (arrived_time..).find_map(|time| {
-        if let Some(&id) = buses.iter().find(|&busid| time % busid == 0) {
-            Some((id, time))
-        } else {
-            None
-        }
-    }) {
-        Some((time - arrived_time) * id)
-    } else {
-        unreachable!()
-    }
+        .collect()
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Equation {
-    modulus: u64,
-    remainder: u64,
-    i_mod: Option<u64>,
-    i_mod_inv: Option<u64>,
+fn parse_notes(input: &str) -> Result<(u64, Vec<(u64, u64)>), ParseError> {
+    let mut lines = input.lines();
+    let (Some(arrived_time), Some(buses)) = (lines.next(), lines.next()) else {
+        return Err(ParseError::msg(
+            "expected the arrival time and the bus ids on two lines",
+        ));
+    };
+    let notes = (parse::unsigned(arrived_time)?, parse_buses(buses)?);
+    Ok(notes)
 }
 
-impl Equation {
-    pub fn new(modulus: u64, remainder: u64) -> Self {
-        Self {
-            modulus,
-            remainder,
-            i_mod: None,
-            i_mod_inv: None,
-        }
-    }
-
-    pub fn fill(&mut self, all_m_prod: u64) -> u64 {
-        let i_mod = all_m_prod / self.modulus;
-        self.i_mod = Some(i_mod);
-        self.i_mod_inv = Some(modular_pow(i_mod, self.modulus as u32 - 2, self.modulus));
-        self.remainder * self.i_mod.unwrap() * self.i_mod_inv.unwrap()
-    }
+pub fn part_one(input: &str) -> anyhow::Result<u64> {
+    let (arrived_time, buses) = parse_notes(input).map_err(|e| e.locate(input))?;
 
-    pub fn get_modulus(&self) -> u64 {
-        self.modulus
-    }
+    buses
+        .iter()
+        .map(|(id, _)| (id, (id - arrived_time % id) % id))
+        .min_by_key(|(_, wait)| *wait)
+        .map(|(id, wait)| id * wait)
+        .ok_or_else(|| anyhow!("no bus is in service"))
 }
 
-#[derive(Copy, Clone, Debug)]
-struct CRTSolver {
-    sums: u64,
-    muls: u64,
-}
+pub fn part_two(input: &str) -> anyhow::Result<u64> {
+    let (_, buses) = parse_notes(input).map_err(|e| e.locate(input))?;
 
-impl CRTSolver {
-    pub fn new(mut equations: Vec<Equation>) -> Self {
-        let muls = equations
-            .iter()
-            .fold(1, |prod, equation| prod * equation.get_modulus());
+    let congruences = buses.iter().map(|(id, idx)| {
+        let id = u128::from(*id);
+        Congruence::new(id - u128::from(*idx) % id, id)
+    });
+    let solution = crt(congruences)?.ok_or_else(|| anyhow!("the buses never line up"))?;
 
-        let sums = equations
-            .iter_mut()
-            .fold(0, |sum, equation| sum + equation.fill(muls));
-
-        Self { muls, sums }
-    }
-
-    pub fn get_minimal(&self) -> u64 {
-        self.sums % self.muls
-    }
-}
-
-pub fn part_two(input: &str) -> Option<u64> {
-    let equations = input
-        .lines()
-        .skip(1)
-        .take(1)
-        .flat_map(|l| l.split(','))
-        .enumerate()
-        .filter_map(|(idx, x)| {
-            if x.chars().take(1).next().unwrap() == 'x' {
-                None
-            } else {
-                let modulus = x.parse::<u64>().unwrap();
-                Some(Equation::new(modulus, modulus - idx as u64 % modulus))
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let solver = CRTSolver::new(equations);
-
-    Some(solver.get_minimal())
-}
-
-fn modular_pow(x: u64, exp: u32, modulo: u64) -> u64 {
-    (match x.checked_pow(exp) {
-        Some(x) => x,
-        None => {
-            let exp_a = exp / 2;
-            let exp_b = exp - exp_a;
-            modular_pow(x, exp_a, modulo) * modular_pow(x, exp_b, modulo)
-        }
-    }) % modulo
+    Ok(u64::try_from(solution.remainder)?)
 }
 
 #[cfg(test)]
@@ -129,15 +57,36 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result.unwrap(), 295);
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result.unwrap(), 1068781);
     }
 
     #[test]
-    fn test_pow() {}
+    fn test_short_examples() {
+        assert_eq!(part_two("0\n17,x,13,19").unwrap(), 3417);
+        assert_eq!(part_two("0\n1789,37,47,1889").unwrap(), 1202161486);
+    }
+
+    #[test]
+    fn test_zero_bus_id() {
+        for result in [part_one("939\n7,0,x"), part_two("939\n7,0,x")] {
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "line 2, column 3: bus ids start at 1, found `0`"
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_solution() {
+        assert_eq!(
+            part_two("0\n4,6").unwrap_err().to_string(),
+            "the buses never line up"
+        );
+    }
 }
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 
+use advent_of_code::parse::{self, ParseError};
 use itertools::Itertools;
 
 advent_of_code::solution!(14);
@@ -59,38 +61,28 @@ enum Instruction {
     WriteMemInst(u64, u64),
 }
 
-impl Instruction {
-    pub fn parse(line: &str) -> Self {
-        peg::parser! {
-            pub(crate) grammar parser() for str {
-                pub rule instruction() -> Instruction
-                = v:(set_mask() / write_mem()) whitespaces() { v }
-
-                rule set_mask() -> Instruction
-                = "mask = " bits:$(['X' | '0' | '1']+) {
-                    let mask = Mask::new(bits);
-                    Instruction::SetMaskInst(mask)
-                }
-
-                rule write_mem() -> Instruction
-                = "mem[" addr:number() "] = " value:number() {
-                    Instruction::WriteMemInst(addr, value)
-                }
-
-                rule number() -> u64
-                = e:$(['0'..='9']+) { e.parse().unwrap() }
-
-                rule whitespaces()
-                = [' ' | '\t' | '\n']*
+impl FromStr for Instruction {
+    type Err = ParseError;
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        if let Some(bits) = line.strip_prefix("mask = ") {
+            let bits = bits.trim();
+            if bits.is_empty() || bits.len() > 64 || !bits.bytes().all(|b| b"X01".contains(&b)) {
+                return Err(ParseError::new("expected a mask of `X`, `0` and `1`", bits));
+            }
+            Ok(Instruction::SetMaskInst(Mask::new(bits)))
+        } else if line.starts_with("mem[") {
+            match parse::uints(line)[..] {
+                [addr, value] => Ok(Instruction::WriteMemInst(addr, value)),
+                _ => Err(ParseError::new("expected `mem[address] = value`", line)),
             }
+        } else {
+            Err(ParseError::new("unknown instruction", line))
         }
-
-        parser::instruction(line).unwrap()
     }
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let insts = input.lines().map(Instruction::parse).collect::<Vec<_>>();
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let insts = parse::lines::<Instruction>(input)?;
     let mut mask;
     let mut mems = HashMap::new();
     let mut addr_masks = vec![];
@@ -107,11 +99,11 @@ pub fn part_two(input: &str) -> Option<u64> {
             }
         }
     }
-    Some(mems.values().sum())
+    Ok(mems.values().sum())
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let insts = input.lines().map(Instruction::parse).collect::<Vec<_>>();
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let insts = parse::lines::<Instruction>(input)?;
     let mut mask = Mask {
         to_set: 0,
         to_clean: 0,
@@ -127,7 +119,7 @@ pub fn part_one(input: &str) -> Option<u64> {
             }
         }
     }
-    Some(mems.values().sum())
+    Ok(mems.values().sum())
 }
 
 #[cfg(test)]
@@ -137,12 +129,14 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        assert_eq!(result, Ok(165));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, None);
+        let result = part_two(&advent_of_code::template::read_file_part(
+            "examples", DAY, 2,
+        ));
+        assert_eq!(result, Ok(208));
     }
 }
@@ -0,0 +1,341 @@
+//! The handheld game console: a program of instructions with one signed argument each, an accumulator and a program
+//! counter.
+//!
+//! The instruction set is pluggable through [`Instruction`]; [`Op`] is the one of 2020 day 08. Programs are parsed
+//! with a shared `peg` grammar for lines like `jmp -4`, and run by a [`Console`] that can step, run until it halts or
+//! until it loops, trace its execution and search for a single instruction to patch.
+
+use std::fmt::Write;
+
+use crate::parse::ParseError;
+
+/// An instruction set for the [`Console`].
+pub trait Instruction: Clone {
+    /// The names of all instructions, in the order they are listed in parse errors.
+    const MNEMONICS: &'static [&'static str];
+
+    /// Creates the instruction for a line like `acc +3`. Returns `None` for an unknown mnemonic.
+    fn decode(mnemonic: &str, argument: i64) -> Option<Self>;
+
+    /// The mnemonic and argument of the instruction, the inverse of [`Instruction::decode`].
+    fn encode(&self) -> (&'static str, i64);
+
+    /// Executes the instruction, including moving the program counter.
+    fn execute(&self, registers: &mut Registers);
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Registers {
+    /// Index of the next instruction.
+    pub pc: i64,
+    pub acc: i64,
+}
+
+/// Why a [`Console`] stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exit {
+    /// The program counter points just past the last instruction.
+    Halted,
+    /// The program counter points anywhere else outside of the program.
+    OutOfBounds,
+    /// The next instruction has already been executed, so the program would repeat forever.
+    Loop,
+}
+
+/// The instructions of 2020 day 08.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Acc(i64),
+    Jmp(i64),
+    Nop(i64),
+}
+
+impl Instruction for Op {
+    const MNEMONICS: &'static [&'static str] = &["acc", "jmp", "nop"];
+
+    fn decode(mnemonic: &str, argument: i64) -> Option<Self> {
+        match mnemonic {
+            "acc" => Some(Op::Acc(argument)),
+            "jmp" => Some(Op::Jmp(argument)),
+            "nop" => Some(Op::Nop(argument)),
+            _ => None,
+        }
+    }
+
+    fn encode(&self) -> (&'static str, i64) {
+        match *self {
+            Op::Acc(argument) => ("acc", argument),
+            Op::Jmp(argument) => ("jmp", argument),
+            Op::Nop(argument) => ("nop", argument),
+        }
+    }
+
+    fn execute(&self, registers: &mut Registers) {
+        match *self {
+            Op::Acc(argument) => {
+                registers.acc += argument;
+                registers.pc += 1;
+            }
+            Op::Jmp(offset) => registers.pc += offset,
+            Op::Nop(_) => registers.pc += 1,
+        }
+    }
+}
+
+impl Op {
+    /// Swaps `jmp` and `nop`, the repair of 2020 day 08.
+    pub fn flipped(&self) -> Option<Op> {
+        match *self {
+            Op::Acc(_) => None,
+            Op::Jmp(argument) => Some(Op::Nop(argument)),
+            Op::Nop(argument) => Some(Op::Jmp(argument)),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+peg::parser! {
+    grammar console_parser() for str {
+        pub rule instruction() -> (&'input str, i64)
+        = mnemonic:$(['a'..='z']+) whitespaces() argument:argument() { (mnemonic, argument) }
+
+        rule whitespaces()
+        = [' ' | '\t']+
+
+        rule argument() -> i64
+        = num:$(['+' | '-'] ['0'..='9']+) {? num.parse().or(Err("64-bit integer")) }
+    }
+}
+
+/// Parses a single line like `jmp -4`.
+pub fn parse_instruction<I: Instruction>(line: &str) -> Result<I, ParseError> {
+    let (mnemonic, argument) =
+        console_parser::instruction(line).map_err(|e| ParseError::from_peg(&e, line))?;
+    I::decode(mnemonic, argument).ok_or_else(|| {
+        let expected = I::MNEMONICS
+            .iter()
+            .map(|m| format!("{m:?}"))
+            .collect::<Vec<_>>();
+        ParseError::new(format!("expected one of {}", expected.join(", ")), mnemonic)
+    })
+}
+
+/// Parses a program with one instruction per line.
+pub fn parse_program<I: Instruction>(input: &str) -> Result<Vec<I>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse_instruction(line).map_err(|e| e.locate(input)))
+        .collect()
+}
+
+/// Lists a program with the address of each instruction, e.g. `  7: jmp -4`.
+pub fn disassemble<I: Instruction>(program: &[I]) -> String {
+    let width = program.len().saturating_sub(1).to_string().len();
+    let mut listing = String::new();
+    for (address, instruction) in program.iter().enumerate() {
+        let (mnemonic, argument) = instruction.encode();
+        writeln!(listing, "{address:>width$}: {mnemonic} {argument:+}").unwrap();
+    }
+    listing
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Debug)]
+pub struct Console<I> {
+    program: Vec<I>,
+    registers: Registers,
+    /// The registers before each executed instruction, if tracing is enabled.
+    trace: Option<Vec<Registers>>,
+}
+
+impl<I: Instruction> Console<I> {
+    pub fn new(program: Vec<I>) -> Self {
+        Console {
+            program,
+            registers: Registers::default(),
+            trace: None,
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Console::new(parse_program(input)?))
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    pub fn program_mut(&mut self) -> &mut [I] {
+        &mut self.program
+    }
+
+    pub fn registers(&self) -> Registers {
+        self.registers
+    }
+
+    /// Resets the registers and the trace, but keeps the program.
+    pub fn reset(&mut self) {
+        self.registers = Registers::default();
+        if let Some(trace) = &mut self.trace {
+            trace.clear();
+        }
+    }
+
+    /// Starts or stops recording the registers before every instruction.
+    pub fn set_tracing(&mut self, enabled: bool) {
+        self.trace = enabled.then(Vec::new);
+    }
+
+    /// The registers before each instruction executed since tracing was enabled, empty if it is not.
+    pub fn trace(&self) -> &[Registers] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// The instruction that the program counter points at, `None` if the program has stopped.
+    pub fn current(&self) -> Option<&I> {
+        usize::try_from(self.registers.pc)
+            .ok()
+            .and_then(|pc| self.program.get(pc))
+    }
+
+    /// Why the program stops at the current program counter, `None` if it can go on.
+    fn exit(&self) -> Option<Exit> {
+        match self.registers.pc {
+            pc if pc == self.program.len() as i64 => Some(Exit::Halted),
+            pc if pc < 0 || pc > self.program.len() as i64 => Some(Exit::OutOfBounds),
+            _ => None,
+        }
+    }
+
+    /// Executes the current instruction. Returns why the program stopped, either before or because of it.
+    pub fn step(&mut self) -> Option<Exit> {
+        if let Some(exit) = self.exit() {
+            return Some(exit);
+        }
+
+        let instruction = self.program[self.registers.pc as usize].clone();
+        if let Some(trace) = &mut self.trace {
+            trace.push(self.registers);
+        }
+        instruction.execute(&mut self.registers);
+        self.exit()
+    }
+
+    /// Runs until the program counter leaves the program, which never happens for a program that loops.
+    pub fn run_until_halt(&mut self) -> Exit {
+        loop {
+            if let Some(exit) = self.step() {
+                return exit;
+            }
+        }
+    }
+
+    /// Runs until the program counter leaves the program, or stops before an instruction would be executed for the
+    /// second time.
+    pub fn run_until_loop(&mut self) -> Exit {
+        let mut executed = vec![false; self.program.len()];
+        loop {
+            if let Some(exit) = self.exit() {
+                return exit;
+            }
+            if std::mem::replace(&mut executed[self.registers.pc as usize], true) {
+                return Exit::Loop;
+            }
+            self.step();
+        }
+    }
+
+    /// Tries replacing each instruction for which `patch` returns a replacement, and runs the patched program from
+    /// the start until it loops. Returns the address of the first patch that makes the program halt, and the
+    /// console after running it.
+    pub fn find_patch(
+        &self,
+        mut patch: impl FnMut(&I) -> Option<I>,
+    ) -> Option<(usize, Console<I>)> {
+        self.program
+            .iter()
+            .enumerate()
+            .find_map(|(address, instruction)| {
+                let mut patched = self.clone();
+                patched.program[address] = patch(instruction)?;
+                patched.reset();
+                (patched.run_until_loop() == Exit::Halted).then_some((address, patched))
+            })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{disassemble, parse_program, Console, Exit, Instruction, Op, Registers};
+
+    const BOOT_CODE: &str =
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+
+    #[test]
+    fn parses_programs() {
+        let program: Vec<Op> = parse_program(BOOT_CODE).unwrap();
+        assert_eq!(program.len(), 9);
+        assert_eq!(program[7], Op::Jmp(-4));
+
+        let error = parse_program::<Op>("nop +0\nmul +3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected one of \"acc\", \"jmp\", \"nop\""
+        );
+        let error = parse_program::<Op>("acc 3").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 5: expected one of [' ' | '\\t'], ['+' | '-']"
+        );
+    }
+
+    #[test]
+    fn disassembles_programs() {
+        let program: Vec<Op> = parse_program(BOOT_CODE).unwrap();
+        let listing = disassemble(&program);
+        assert_eq!(listing.lines().next(), Some("0: nop +0"));
+        assert_eq!(listing.lines().nth(7), Some("7: jmp -4"));
+
+        let listing = disassemble(&[Op::Nop(0); 11]);
+        assert_eq!(listing.lines().nth(9), Some(" 9: nop +0"));
+        assert_eq!(listing.lines().nth(10), Some("10: nop +0"));
+    }
+
+    #[test]
+    fn runs_until_loop() {
+        let mut console = Console::<Op>::parse(BOOT_CODE).unwrap();
+        console.set_tracing(true);
+        assert_eq!(console.run_until_loop(), Exit::Loop);
+        assert_eq!(console.registers(), Registers { pc: 1, acc: 5 });
+        assert_eq!(
+            console.trace().iter().map(|r| r.pc).collect::<Vec<_>>(),
+            vec![0, 1, 2, 6, 7, 3, 4]
+        );
+    }
+
+    #[test]
+    fn stops_outside_of_the_program() {
+        let mut console = Console::new(vec![Op::Acc(2), Op::Jmp(-5)]);
+        assert_eq!(console.step(), None);
+        assert_eq!(console.step(), Some(Exit::OutOfBounds));
+        assert_eq!(console.current(), None);
+
+        let mut console = Console::new(vec![Op::Nop(0), Op::Acc(7)]);
+        assert_eq!(console.run_until_halt(), Exit::Halted);
+        assert_eq!(console.registers().acc, 7);
+    }
+
+    #[test]
+    fn finds_patches() {
+        let console = Console::<Op>::parse(BOOT_CODE).unwrap();
+        let (address, patched) = console.find_patch(Op::flipped).unwrap();
+        assert_eq!(address, 7);
+        assert_eq!(patched.registers().acc, 8);
+        assert_eq!(patched.program()[7].encode(), ("nop", -4));
+        assert_eq!(console.program()[7], Op::Jmp(-4));
+    }
+}
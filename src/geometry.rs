@@ -0,0 +1,266 @@
+//! Points, directions and turns on a two-dimensional integer plane.
+//!
+//! Coordinates follow [`crate::grid`] and [`crate::visualize`]: `x` grows to the right and `y` grows downwards, so
+//! north is `(0, -1)` and turning right rotates clockwise on screen.
+
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A displacement between two points, e.g. a waypoint relative to a ship.
+pub type Vec2 = Point;
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Point { x, y }
+    }
+
+    /// Sum of the absolute differences of the coordinates.
+    pub fn manhattan(self, other: Point) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Manhattan distance to the origin.
+    pub fn manhattan_len(self) -> u64 {
+        self.manhattan(Point::ORIGIN)
+    }
+
+    /// Rotates around the origin by 90° clockwise.
+    pub fn rotate_right(self) -> Self {
+        Point::new(-self.y, self.x)
+    }
+
+    /// Rotates around the origin by 90° counter-clockwise.
+    pub fn rotate_left(self) -> Self {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Rotates around the origin in the direction of `turn`.
+    pub fn rotate(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Straight => self,
+            Turn::Right => self.rotate_right(),
+            Turn::Around => -self,
+            Turn::Left => self.rotate_left(),
+        }
+    }
+
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from((x, y): (i64, i64)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl From<Point> for (i64, i64) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl MulAssign<i64> for Point {
+    fn mul_assign(&mut self, rhs: i64) {
+        *self = *self * rhs;
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A change of direction by a multiple of 90°.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Straight,
+    Right,
+    Around,
+    Left,
+}
+
+impl Turn {
+    /// Converts a clockwise angle, e.g. `-90` for a left turn. Returns `None` unless it is a multiple of 90°.
+    pub fn from_degrees(degrees: i64) -> Option<Self> {
+        if degrees % 90 != 0 {
+            return None;
+        }
+        Some(match degrees.rem_euclid(360) / 90 {
+            0 => Turn::Straight,
+            1 => Turn::Right,
+            2 => Turn::Around,
+            _ => Turn::Left,
+        })
+    }
+
+    pub fn degrees(self) -> i64 {
+        match self {
+            Turn::Straight => 0,
+            Turn::Right => 90,
+            Turn::Around => 180,
+            Turn::Left => 270,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// All directions, clockwise starting at north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The vector of a single step in this direction.
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Direction::North => Point::new(0, -1),
+            Direction::East => Point::new(1, 0),
+            Direction::South => Point::new(0, 1),
+            Direction::West => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        Direction::ALL[(self as usize + turn.degrees() as usize / 90) % 4]
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.turn(Turn::Right)
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.turn(Turn::Left)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn(Turn::Around)
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    /// Accepts compass letters (`N`, `E`, `S`, `W`) and arrows (`^`, `>`, `v`, `<`).
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'N' | '^' => Ok(Direction::North),
+            'E' | '>' => Ok(Direction::East),
+            'S' | 'v' => Ok(Direction::South),
+            'W' | '<' => Ok(Direction::West),
+            _ => Err(format!("`{c}` is not a direction")),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Direction, Point, Turn};
+
+    #[test]
+    fn does_arithmetic() {
+        let mut p = Point::new(1, 2);
+        p += Point::new(3, -4) * 2;
+        assert_eq!(p, Point::new(7, -6));
+        assert_eq!(p - Point::new(7, 0), Point::new(0, -6));
+        assert_eq!(-p, Point::new(-7, 6));
+        assert_eq!(p.manhattan_len(), 13);
+        assert_eq!(p.manhattan(Point::new(-1, -1)), 13);
+    }
+
+    #[test]
+    fn rotates_points() {
+        let p = Point::new(10, -4);
+        assert_eq!(p.rotate_right(), Point::new(4, 10));
+        assert_eq!(p.rotate_left(), Point::new(-4, -10));
+        assert_eq!(p.rotate(Turn::Around), Point::new(-10, 4));
+        assert_eq!(p.rotate(Turn::Straight), p);
+        assert_eq!(
+            Direction::North.offset().rotate_right(),
+            Direction::East.offset()
+        );
+    }
+
+    #[test]
+    fn turns_directions() {
+        assert_eq!(Turn::from_degrees(-90), Some(Turn::Left));
+        assert_eq!(Turn::from_degrees(450), Some(Turn::Right));
+        assert_eq!(Turn::from_degrees(-180), Some(Turn::Around));
+        assert_eq!(Turn::from_degrees(45), None);
+
+        assert_eq!(Direction::East.turn_right(), Direction::South);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::West.reverse(), Direction::East);
+        assert_eq!(Point::ORIGIN.step(Direction::South), Point::new(0, 1));
+        assert_eq!(Direction::try_from('<'), Ok(Direction::West));
+        assert!(Direction::try_from('x').is_err());
+    }
+}
@@ -0,0 +1,369 @@
+//! Directed graphs with weighted edges, e.g. bags that contain other bags or adapters that fit into each other.
+//!
+//! Nodes are interned: [`Graph::node`] assigns each distinct node a [`NodeId`] once, and everything else works on ids.
+//! Graphs without weights use `()` as the weight.
+
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Index of a node in the order it was first added.
+pub type NodeId = usize;
+
+#[derive(Clone, Debug)]
+pub struct Graph<N, W = ()> {
+    nodes: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    edges: Vec<Vec<(NodeId, W)>>,
+}
+
+impl<N, W> Default for Graph<N, W> {
+    fn default() -> Self {
+        Graph {
+            nodes: vec![],
+            ids: HashMap::new(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash, W> Graph<N, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `node`, adding it if it is new.
+    pub fn node(&mut self, node: N) -> NodeId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.edges.push(vec![]);
+        id
+    }
+
+    /// Looks up the id of a node, e.g. by `&str` for a graph of `String`s.
+    pub fn id<Q>(&self, node: &Q) -> Option<NodeId>
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.ids.get(node).copied()
+    }
+
+    /// Adds an edge, and its nodes if they are new. Returns the ids of both nodes.
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) -> (NodeId, NodeId) {
+        let (from, to) = (self.node(from), self.node(to));
+        self.add_edge_ids(from, to, weight);
+        (from, to)
+    }
+
+    pub fn add_edge_ids(&mut self, from: NodeId, to: NodeId, weight: W) {
+        self.edges[from].push((to, weight));
+    }
+
+    /// The graph with the direction of every edge flipped.
+    pub fn reversed(&self) -> Self
+    where
+        W: Clone,
+    {
+        let mut edges = vec![vec![]; self.nodes.len()];
+        for (from, targets) in self.edges.iter().enumerate() {
+            for (to, weight) in targets {
+                edges[*to].push((from, weight.clone()));
+            }
+        }
+        Graph {
+            nodes: self.nodes.clone(),
+            ids: self.ids.clone(),
+            edges,
+        }
+    }
+}
+
+impl<N, W> Graph<N, W> {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The node that `id` stands for.
+    pub fn name(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    /// Outgoing edges of `id` with their weights, in the order they were added.
+    pub fn edges(&self, id: NodeId) -> &[(NodeId, W)] {
+        &self.edges[id]
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[id].iter().map(|(to, _)| *to)
+    }
+
+    /// Nodes reachable from `start` in breadth-first order, starting with `start` itself.
+    pub fn bfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut order = vec![];
+        let mut queue = VecDeque::from([start]);
+        seen[start] = true;
+
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            for next in self.neighbours(id) {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        order
+    }
+
+    /// Nodes reachable from `start` in depth-first pre-order, starting with `start` itself.
+    pub fn dfs(&self, start: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut order = vec![];
+        let mut stack = vec![start];
+
+        while let Some(id) = stack.pop() {
+            if std::mem::replace(&mut seen[id], true) {
+                continue;
+            }
+            order.push(id);
+            // NOTE: pushed in reverse, so that edges are followed in the order they were added.
+            stack.extend(
+                self.neighbours(id)
+                    .filter(|next| !seen[*next])
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev(),
+            );
+        }
+        order
+    }
+
+    /// All nodes, ordered so that every edge points forward. Returns `None` if the graph has a cycle.
+    pub fn topological_order(&self) -> Option<Vec<NodeId>> {
+        self.order_of(&(0..self.len()).collect::<Vec<_>>())
+    }
+
+    /// Orders `nodes` so that every edge between two of them points forward, ignoring all other nodes. Returns
+    /// `None` if they contain a cycle.
+    fn order_of(&self, nodes: &[NodeId]) -> Option<Vec<NodeId>> {
+        let mut included = vec![false; self.len()];
+        for id in nodes {
+            included[*id] = true;
+        }
+
+        let mut incoming = vec![0; self.len()];
+        for id in nodes {
+            for next in self.neighbours(*id) {
+                incoming[next] += 1;
+            }
+        }
+
+        let mut queue: VecDeque<NodeId> = nodes
+            .iter()
+            .copied()
+            .filter(|id| incoming[*id] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(nodes.len());
+
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            for next in self.neighbours(id).filter(|next| included[*next]) {
+                incoming[next] -= 1;
+                if incoming[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        (order.len() == nodes.len()).then_some(order)
+    }
+
+    /// Computes a value for `start` from the values of the nodes its edges lead to, recursively. `f` gets a node and
+    /// the weight and value of each of its outgoing edges. Every node is evaluated once, so shared sub-graphs are
+    /// not recomputed. Returns `None` if a cycle is reachable from `start`.
+    ///
+    /// ```
+    /// # use advent_of_code::graph::Graph;
+    /// // bags inside a bag: each edge holds `weight` bags, plus the bags inside those.
+    /// # let graph: Graph<&str, u64> = Graph::new();
+    /// # let start = 0;
+    /// graph.accumulate(start, |_, children| children.map(|(n, inside)| n * (1 + inside)).sum::<u64>());
+    /// ```
+    pub fn accumulate<T>(
+        &self,
+        start: NodeId,
+        mut f: impl FnMut(NodeId, &mut dyn Iterator<Item = (&W, &T)>) -> T,
+    ) -> Option<T> {
+        // NOTE: evaluated from the last node of the order backwards, so that every node comes after the nodes its
+        // edges lead to, without recursing along long chains.
+        let order = self.order_of(&self.dfs(start))?;
+        let mut values: Vec<Option<T>> = (0..self.len()).map(|_| None).collect();
+
+        for id in order.into_iter().rev() {
+            let value = f(
+                id,
+                &mut self.edges[id]
+                    .iter()
+                    .map(|(to, weight)| (weight, values[*to].as_ref().unwrap())),
+            );
+            values[id] = Some(value);
+        }
+
+        values[start].take()
+    }
+
+    /// Number of distinct paths from `from` to `to`. Returns `None` if a cycle is reachable from `from`.
+    pub fn count_paths(&self, from: NodeId, to: NodeId) -> Option<u64> {
+        self.accumulate(from, |id, children| {
+            if id == to {
+                1
+            } else {
+                children.map(|(_, paths)| paths).sum()
+            }
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Graph;
+
+    /// The bags of the example of day 07, as a graph of how many bags each bag contains.
+    fn bags() -> Graph<String, u64> {
+        let rules = [
+            ("light red", "bright white", 1),
+            ("light red", "muted yellow", 2),
+            ("dark orange", "bright white", 3),
+            ("dark orange", "muted yellow", 4),
+            ("bright white", "shiny gold", 1),
+            ("muted yellow", "shiny gold", 2),
+            ("muted yellow", "faded blue", 9),
+            ("shiny gold", "dark olive", 1),
+            ("shiny gold", "vibrant plum", 2),
+            ("dark olive", "faded blue", 3),
+            ("dark olive", "dotted black", 4),
+            ("vibrant plum", "faded blue", 5),
+            ("vibrant plum", "dotted black", 6),
+        ];
+        let mut graph = Graph::new();
+        for (outer, inner, count) in rules {
+            graph.add_edge(outer.to_string(), inner.to_string(), count);
+        }
+        graph
+    }
+
+    #[test]
+    fn interns_nodes() {
+        let mut graph = bags();
+        assert_eq!(graph.len(), 9);
+        let gold = graph.id("shiny gold").unwrap();
+        assert_eq!(graph.name(gold), "shiny gold");
+        assert_eq!(graph.node("shiny gold".to_string()), gold);
+        assert_eq!(graph.id("mirrored red"), None);
+    }
+
+    #[test]
+    fn traverses_graphs() {
+        let graph = bags();
+        let gold = graph.id("shiny gold").unwrap();
+        let names = |ids: Vec<usize>| {
+            ids.into_iter()
+                .map(|id| graph.name(id).as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            names(graph.bfs(gold)),
+            vec![
+                "shiny gold",
+                "dark olive",
+                "vibrant plum",
+                "faded blue",
+                "dotted black"
+            ]
+        );
+        assert_eq!(
+            names(graph.dfs(gold)),
+            vec![
+                "shiny gold",
+                "dark olive",
+                "faded blue",
+                "dotted black",
+                "vibrant plum"
+            ]
+        );
+
+        let containers = graph.reversed();
+        assert_eq!(containers.bfs(gold).len() - 1, 4);
+        assert_eq!(containers.edges(gold).len(), 2);
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let graph = bags();
+        let order = graph.topological_order().unwrap();
+        let position = |id| order.iter().position(|x| *x == id).unwrap();
+        for id in 0..graph.len() {
+            for next in graph.neighbours(id) {
+                assert!(position(id) < position(next));
+            }
+        }
+
+        let mut cyclic: Graph<u8> = Graph::new();
+        cyclic.add_edge(1, 2, ());
+        cyclic.add_edge(2, 1, ());
+        assert_eq!(cyclic.topological_order(), None);
+    }
+
+    #[test]
+    fn accumulates_over_dags() {
+        let graph = bags();
+        let gold = graph.id("shiny gold").unwrap();
+        let inside = graph.accumulate(gold, |_, children| {
+            children
+                .map(|(count, inside)| count * (1 + inside))
+                .sum::<u64>()
+        });
+        assert_eq!(inside, Some(32));
+
+        let red = graph.id("light red").unwrap();
+        let black = graph.id("dotted black").unwrap();
+        assert_eq!(graph.count_paths(red, black), Some(4));
+        assert_eq!(graph.count_paths(black, red), Some(0));
+    }
+
+    #[test]
+    fn accumulates_over_long_chains() {
+        let mut chain: Graph<u32> = Graph::new();
+        for i in 0..200_000 {
+            chain.add_edge(i, i + 1, ());
+        }
+        assert_eq!(chain.count_paths(0, 200_000), Some(1));
+        assert_eq!(
+            chain.accumulate(0, |_, next| 1 + next.map(|(_, n)| n).sum::<u64>()),
+            Some(200_001)
+        );
+    }
+
+    #[test]
+    fn detects_reachable_cycles() {
+        let mut graph: Graph<u8> = Graph::new();
+        graph.add_edge(0, 1, ());
+        graph.add_edge(2, 3, ());
+        graph.add_edge(3, 2, ());
+        assert_eq!(graph.count_paths(0, 1), Some(1));
+        assert_eq!(graph.count_paths(2, 3), None);
+    }
+}
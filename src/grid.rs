@@ -0,0 +1,496 @@
+//! A two-dimensional grid of cells, as found in many puzzle inputs.
+//!
+//! Cells are addressed as `(x, y)`, where `x` is the column and `y` the row, starting at the top left. Indexing with
+//! `grid[(x, y)]` panics outside of the grid, [`Grid::get`] returns `None` instead and [`Grid::get_wrapping`] repeats
+//! the grid infinitely in every direction.
+
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the four orthogonal neighbours: up, right, down and left.
+pub const NEIGHBOURS_4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the eight orthogonal and diagonal neighbours, clockwise starting at the top left.
+pub const NEIGHBOURS_8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    Empty,
+    /// A line is not as long as the first one.
+    Ragged {
+        line: usize,
+    },
+    /// A character that the cell parser did not accept.
+    InvalidCell {
+        line: usize,
+        column: usize,
+        c: char,
+    },
+}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "the grid is empty"),
+            ParseGridError::Ragged { line } => {
+                write!(f, "line {line} is not as long as the first line")
+            }
+            ParseGridError::InvalidCell { line, column, c } => {
+                write!(f, "invalid cell `{c}` on line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+impl Grid<char> {
+    /// Parses a grid with one cell per character. Lines have to be of the same length, a trailing newline is ignored.
+    ///
+    /// ```
+    /// let grid = advent_of_code::grid::Grid::parse("#.\n.#\n").unwrap();
+    /// assert_eq!(grid[(1, 1)], '#');
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, ParseGridError> {
+        Self::parse_with(input, Some)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses a grid, converting each character with `cell`. Returns an error for characters it maps to `None`.
+    pub fn parse_with(
+        input: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseGridError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+
+        for (y, line) in input.lines().enumerate() {
+            let before = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                let parsed = cell(c).ok_or(ParseGridError::InvalidCell {
+                    line: y + 1,
+                    column: x + 1,
+                    c,
+                })?;
+                cells.push(parsed);
+            }
+
+            let line_width = cells.len() - before;
+            if *width.get_or_insert(line_width) != line_width {
+                return Err(ParseGridError::Ragged { line: y + 1 });
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(ParseGridError::Empty),
+        }
+    }
+
+    /// Creates a grid from cells in row-major order. Returns `None` if there are no cells, or they do not fill whole
+    /// rows.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Option<Self> {
+        if width == 0 || cells.is_empty() || !cells.len().is_multiple_of(width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        })
+    }
+
+    /// Creates a grid with every cell set to `fill`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `height` is `0`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        assert!(
+            width > 0 && height > 0,
+            "a grid of {width}x{height} has no cells"
+        );
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y)
+    }
+
+    /// Returns the cell at `(x, y)`, or `None` outside of the grid.
+    pub fn get(&self, x: i64, y: i64) -> Option<&T> {
+        self.contains(x, y)
+            .then(|| &self.cells[y as usize * self.width + x as usize])
+    }
+
+    pub fn get_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        if self.contains(x, y) {
+            Some(&mut self.cells[y as usize * self.width + x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cell at `(x, y)` of a grid that repeats infinitely in every direction.
+    pub fn get_wrapping(&self, x: i64, y: i64) -> &T {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        &self[(x, y)]
+    }
+
+    /// Positions of all cells, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// All cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// All cells in row-major order, the cell at `(x, y)` is at index `y * width + x`.
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// All cells with their position, row by row.
+    pub fn enumerate(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Positions of the orthogonal neighbours of `(x, y)` that are inside the grid.
+    pub fn neighbours_4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &NEIGHBOURS_4)
+    }
+
+    /// Positions of the orthogonal and diagonal neighbours of `(x, y)` that are inside the grid.
+    pub fn neighbours_8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &NEIGHBOURS_8)
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(i64, i64)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let (x, y) = (x as i64 + dx, y as i64 + dy);
+            self.contains(x, y).then_some((x as usize, y as usize))
+        })
+    }
+
+    /// Positions along a ray from `(x, y)` in steps of `(dx, dy)`, excluding the start, up to the edge of the grid.
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        (dx, dy): (i64, i64),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let start = (x as i64, y as i64);
+        (1..)
+            .map(move |i| (start.0 + dx * i, start.1 + dy * i))
+            .take_while(move |(x, y)| (dx, dy) != (0, 0) && self.contains(*x, *y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            x < self.width,
+            "x {x} is outside of a grid of width {}",
+            self.width
+        );
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Diagonals running down and to the right, starting at the bottom left corner and ending at the top right one.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.height)
+            .rev()
+            .map(|y| (0, y))
+            .chain((1..self.width).map(|x| (x, 0)));
+        starts.map(|(x, y)| {
+            std::iter::once((x, y))
+                .chain(self.ray(x, y, (1, 1)))
+                .map(|p| &self[p])
+        })
+    }
+
+    /// Diagonals running down and to the left, starting at the top left corner and ending at the bottom right one.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.width)
+            .map(|x| (x, 0))
+            .chain((1..self.height).map(|y| (self.width - 1, y)));
+        starts.map(|(x, y)| {
+            std::iter::once((x, y))
+                .chain(self.ray(x, y, (-1, 1)))
+                .map(|p| &self[p])
+        })
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, rows become columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+
+    /// Rotates the grid by 90° clockwise.
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| (0..self.height).rev().map(move |y| (x, y)))
+                .map(|p| self[p].clone())
+                .collect(),
+        }
+    }
+
+    /// Rotates the grid by 90° counter-clockwise.
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .rev()
+                .flat_map(|x| (0..self.height).map(move |y| (x, y)))
+                .map(|p| self[p].clone())
+                .collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        assert!(
+            x < self.width,
+            "x {x} is outside of a grid of width {}",
+            self.width
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            x < self.width,
+            "x {x} is outside of a grid of width {}",
+            self.width
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Renders the grid with one line per row, without separators between cells.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Grid, ParseGridError};
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        let digits = Grid::parse_with("12\n34", |c| c.to_digit(10)).unwrap();
+        assert_eq!(digits.iter().sum::<u32>(), 10);
+    }
+
+    #[test]
+    fn rejects_invalid_grids() {
+        assert_eq!(Grid::parse(""), Err(ParseGridError::Empty));
+        assert_eq!(
+            Grid::parse("ab\nabc"),
+            Err(ParseGridError::Ragged { line: 2 })
+        );
+        assert_eq!(
+            Grid::parse_with("12\n3x", |c| c.to_digit(10)),
+            Err(ParseGridError::InvalidCell {
+                line: 2,
+                column: 2,
+                c: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_empty_grids() {
+        assert_eq!(Grid::<char>::from_vec(0, vec![]), None);
+        assert_eq!(Grid::<char>::from_vec(3, vec![]), None);
+        assert_eq!(Grid::from_vec(0, vec!['a']), None);
+        assert!(Grid::from_vec(1, vec!['a']).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "a grid of 0x2 has no cells")]
+    fn panics_on_empty_dimensions() {
+        Grid::new(0, 2, '.');
+    }
+
+    #[test]
+    fn indexes_checked_and_wrapping() {
+        let grid = grid();
+        assert_eq!(grid.get(0, 0), Some(&'a'));
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.get_wrapping(-1, 0), &'c');
+        assert_eq!(grid.get_wrapping(4, 3), &'e');
+    }
+
+    #[test]
+    fn finds_neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours_4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours_8(1, 0).count(), 5);
+        assert_eq!(grid.neighbours_8(1, 1).count(), 5);
+        assert_eq!(
+            grid.ray(0, 0, (1, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (2, 0)]
+        );
+        assert_eq!(grid.ray(0, 0, (0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn iterates_lines() {
+        let grid = grid();
+        let collect = |cells: &mut dyn Iterator<Item = &char>| cells.collect::<String>();
+
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        assert_eq!(collect(&mut grid.column(2)), "cf");
+        assert_eq!(
+            grid.diagonals()
+                .map(|mut d| collect(&mut d))
+                .collect::<Vec<_>>(),
+            vec!["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            grid.anti_diagonals()
+                .map(|mut d| collect(&mut d))
+                .collect::<Vec<_>>(),
+            vec!["a", "bd", "ce", "f"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of a grid of width 3")]
+    fn rejects_columns_outside_of_the_grid() {
+        let _ = grid().column(3);
+    }
+
+    #[test]
+    fn transforms_grids() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_right().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_left().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+        assert_eq!(
+            Grid::from_vec(2, vec![1, 2, 3, 4]).unwrap().to_string(),
+            "12\n34"
+        );
+        assert_eq!(Grid::from_vec(3, vec![1, 2, 3, 4]), None);
+    }
+}
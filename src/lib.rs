@@ -1,3 +1,13 @@
+pub mod automaton;
+pub mod console;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod number_theory;
+pub mod ocr;
+pub mod parse;
+pub mod search;
 pub mod template;
+pub mod visualize;
 
 // Use this file to add helper functions and additional modules.
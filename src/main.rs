@@ -1,144 +1,282 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
-use args::{parse, AppArguments};
+use advent_of_code::template::commands::status::DEFAULT_TEST_TIMEOUT;
+use advent_of_code::template::commands::{
+    all, download, inputs, journal, leaderboard, read, scaffold, solve, status, time,
+};
+use advent_of_code::template::config;
+use advent_of_code::visualize::Output;
+use args::{parse, print_completions, AppArguments, InputsCommand};
 
 #[cfg(feature = "today")]
-use advent_of_code::template::Day;
-#[cfg(feature = "today")]
-use std::process;
+use advent_of_code::template::commands::today;
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::{
+        config,
+        runner::{parse_timeout, RunOptions},
+        Day,
+    };
+    use advent_of_code::visualize::Output;
+    use clap::builder::{PossibleValuesParser, TypedValueParser};
+    use clap::{Args, CommandFactory, Parser, Subcommand};
+    use clap_complete::Shell;
+    use std::{env, path::PathBuf, time::Duration};
+
+    /// Scaffold, run and benchmark Advent of Code solutions.
+    #[derive(Parser)]
+    #[command(version, arg_required_else_help = true)]
+    struct Cli {
+        #[command(subcommand)]
+        command: AppArguments,
+        /// Year of the puzzles, overrides `year` in the configuration file.
+        #[arg(long, global = true, env = "AOC_YEAR")]
+        year: Option<u16>,
+        /// Configuration file to read instead of `aoc.toml`.
+        #[arg(long, global = true, value_name = "FILE", env = "AOC_CONFIG")]
+        config: Option<PathBuf>,
+    }
 
+    #[derive(Subcommand)]
     pub enum AppArguments {
+        /// Download the input and puzzle description of a day via aoc-cli.
         Download {
+            /// Day of the puzzle, from 1 to 25.
             day: Day,
         },
+        /// Show the stored puzzle description of a day.
         Read {
+            /// Day of the puzzle, from 1 to 25.
             day: Day,
         },
+        /// Create the solution and data files of a day.
         Scaffold {
+            /// Day of the puzzle, from 1 to 25.
             day: Day,
+            /// Also download the input and puzzle description.
+            #[arg(long)]
             download: bool,
+            /// Replace an existing solution file.
+            #[arg(long)]
             overwrite: bool,
         },
+        /// Run the solution of a day against its input.
         Solve {
+            /// Day of the puzzle, from 1 to 25.
             day: Day,
+            /// Run an optimized build.
+            #[arg(long)]
             release: bool,
+            /// Profile heap allocations with DHAT.
+            #[arg(long, conflicts_with = "alloc_stats")]
             dhat: bool,
+            /// Count the allocations of every part.
+            #[arg(long)]
+            alloc_stats: bool,
+            /// Play the frames recorded by the solution as a terminal animation.
+            #[arg(long)]
+            visualize: bool,
+            /// Write the frames recorded by the solution to `data/visualizations` instead.
+            #[arg(long, value_name = "FORMAT", value_parser = parse_export())]
+            export: Option<Output>,
+            /// Submit the answer of a part via aoc-cli.
+            #[arg(long, value_name = "PART", value_parser = clap::value_parser!(u8).range(1..=2))]
             submit: Option<u8>,
+            #[command(flatten)]
+            options: RunArgs,
         },
+        /// Run the solutions of all days.
         All {
+            /// Run optimized builds.
+            #[arg(long)]
             release: bool,
+            /// Count the allocations of every part.
+            #[arg(long)]
+            alloc_stats: bool,
+            #[command(flatten)]
+            options: RunArgs,
         },
+        /// Benchmark solutions, by default the days that are not fully benchmarked yet.
         Time {
-            all: bool,
+            /// The day to benchmark.
             day: Option<Day>,
+            /// Benchmark all days, including the ones that are benchmarked already.
+            #[arg(long, conflicts_with = "day")]
+            all: bool,
+            /// Store the timings and update the benchmark table in the readme.
+            #[arg(long)]
             store: bool,
+            /// Write an HTML report of all stored timings to this directory.
+            #[arg(long, value_name = "DIR")]
+            report: Option<PathBuf>,
+            /// Count the allocations of every part.
+            #[arg(long)]
+            alloc_stats: bool,
+            #[command(flatten)]
+            options: RunArgs,
         },
+        /// Encrypt puzzle inputs, so that they can be committed.
+        Inputs {
+            #[command(subcommand)]
+            command: InputsCommand,
+        },
+        /// Report how long solving each day took, from the journal of scaffolds, downloads and submissions.
+        Journal {
+            /// Only report this day.
+            day: Option<Day>,
+        },
+        /// Show the ranking and star times of a private leaderboard.
+        Leaderboard {
+            /// Id of the leaderboard, the number at the end of its URL.
+            id: u64,
+        },
+        /// Print an overview of the state of every day.
+        Status {
+            /// Do not run the example tests.
+            #[arg(long)]
+            no_tests: bool,
+            /// Kill the tests of a day after this many seconds [default: 10].
+            #[arg(long, value_name = "SECONDS", value_parser = parse_timeout)]
+            timeout: Option<Duration>,
+        },
+        /// Print a completion script for the `advent_of_code` binary.
+        Completions { shell: Shell },
+        /// Scaffold, download and read the puzzle of the current day.
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            /// Count down to the next puzzle and fetch it as soon as it unlocks.
+            #[arg(long)]
+            wait: bool,
+        },
     }
 
-    pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
+    #[derive(Subcommand)]
+    pub enum InputsCommand {
+        /// Create a new key file.
+        Keygen,
+        /// Encrypt all plaintext inputs.
+        Encrypt,
+        /// Fail if plaintext inputs are staged for a commit, for use in a pre-commit hook.
+        Check,
+    }
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-            },
-            Some("time") => {
-                let all = args.contains("--all");
-                let store = args.contains("--store");
+    /// Options that control how solution parts are run.
+    #[derive(Args)]
+    pub struct RunArgs {
+        /// Stop waiting for a part after this many seconds.
+        #[arg(long, value_name = "SECONDS", value_parser = parse_timeout)]
+        timeout: Option<Duration>,
+        /// Stack size of the threads parts run on [default: 64].
+        #[arg(long, value_name = "MiB")]
+        stack_size: Option<usize>,
+    }
 
-                AppArguments::Time {
-                    all,
-                    day: args.opt_free_from_str()?,
-                    store,
-                }
-            }
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
-            },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
-            },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-                download: args.contains("--download"),
-                overwrite: args.contains("--overwrite"),
-            },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-            },
-            #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
+    impl From<RunArgs> for RunOptions {
+        fn from(args: RunArgs) -> Self {
+            let defaults = &config::get().run;
+            RunOptions {
+                timeout: args.timeout.or(defaults.timeout),
+                stack_size_mib: args.stack_size.or(defaults.stack_size_mib),
             }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
-            }
-        };
+        }
+    }
 
-        let remaining = args.finish();
-        if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
+    fn parse_export() -> impl TypedValueParser<Value = Output> {
+        PossibleValuesParser::new(["ppm", "png", "svg"]).map(|s| s.parse::<Output>().unwrap())
+    }
+
+    /// Parses the arguments. Global options are exported to the environment, so that the configuration and the
+    /// solution binaries started by commands pick them up.
+    pub fn parse() -> AppArguments {
+        let cli = Cli::parse();
+        if let Some(year) = cli.year {
+            env::set_var("AOC_YEAR", year.to_string());
+        }
+        if let Some(config) = cli.config {
+            env::set_var("AOC_CONFIG", config);
         }
+        cli.command
+    }
 
-        Ok(app_args)
+    pub fn print_completions(shell: Shell) {
+        clap_complete::generate(
+            shell,
+            &mut Cli::command(),
+            env!("CARGO_BIN_NAME"),
+            &mut std::io::stdout(),
+        );
     }
 }
 
 fn main() {
     match parse() {
-        Err(err) => {
-            eprintln!("Error: {err}");
-            std::process::exit(1);
-        }
-        Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold {
-                day,
-                download,
-                overwrite,
-            } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
-                }
-            }
-            AppArguments::Solve {
+        AppArguments::All {
+            release,
+            alloc_stats,
+            options,
+        } => all::handle(release, alloc_stats, &options.into()),
+        AppArguments::Time {
+            day,
+            all,
+            store,
+            report,
+            alloc_stats,
+            options,
+        } => {
+            let defaults = &config::get().bench;
+            time::handle(
                 day,
-                release,
-                dhat,
-                submit,
-            } => solve::handle(day, release, dhat, submit),
-            #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
+                all,
+                store || defaults.store,
+                report
+                    .as_ref()
+                    .or(defaults.report_dir.as_ref())
+                    .map(|p| p.as_path()),
+                alloc_stats || defaults.alloc_stats,
+                &options.into(),
+            );
+        }
+        AppArguments::Download { day } => download::handle(day),
+        AppArguments::Read { day } => read::handle(day),
+        AppArguments::Scaffold {
+            day,
+            download,
+            overwrite,
+        } => {
+            scaffold::handle(day, overwrite);
+            if download {
+                download::handle(day);
             }
+        }
+        AppArguments::Solve {
+            day,
+            release,
+            dhat,
+            alloc_stats,
+            visualize,
+            export,
+            submit,
+            options,
+        } => solve::handle(
+            day,
+            release,
+            dhat,
+            alloc_stats,
+            // exporting frames implies visualizing them.
+            export.or(visualize.then_some(Output::Terminal)),
+            submit,
+            &options.into(),
+        ),
+        AppArguments::Status { no_tests, timeout } => {
+            status::handle(!no_tests, timeout.unwrap_or(DEFAULT_TEST_TIMEOUT));
+        }
+        AppArguments::Inputs { command } => match command {
+            InputsCommand::Keygen => inputs::keygen(),
+            InputsCommand::Encrypt => inputs::encrypt(),
+            InputsCommand::Check => inputs::check(),
         },
+        AppArguments::Journal { day } => journal::handle(day),
+        AppArguments::Leaderboard { id } => leaderboard::handle(id),
+        AppArguments::Completions { shell } => print_completions(shell),
+        #[cfg(feature = "today")]
+        AppArguments::Today { wait } => today::handle(wait),
     };
 }
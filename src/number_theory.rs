@@ -0,0 +1,556 @@
+//! Greatest common divisors, modular inverses, the Chinese remainder theorem and discrete logarithms.
+//!
+//! Modular arithmetic works on `u128` and never overflows, also for moduli close to `u128::MAX`: products that do
+//! not fit are computed by doubling and adding. [`ModInt`] and [`DynModInt`] wrap `u64` residues for code that reads
+//! better with operators.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Greatest common divisor, `gcd(0, 0)` is `0`.
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` if it does not fit into a `u128`.
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)`.
+pub fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+pub fn addmod(a: u128, b: u128, m: u128) -> u128 {
+    let (a, b) = (a % m, b % m);
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+pub fn submod(a: u128, b: u128, m: u128) -> u128 {
+    let (a, b) = (a % m, b % m);
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+/// `a * b mod m`, without overflowing.
+pub fn mulmod(a: u128, b: u128, m: u128) -> u128 {
+    let (mut a, mut b) = (a % m, b % m);
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = addmod(result, a, m);
+        }
+        a = addmod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+/// `base ^ exp mod m`, by squaring.
+pub fn powmod(base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut base = base % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mulmod(result, base, m);
+        }
+        base = mulmod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The `x` with `a * x ≡ 1 (mod m)`, or `None` if `a` and `m` are not coprime.
+pub fn modinv(a: u128, m: u128) -> Option<u128> {
+    if m == 0 {
+        return None;
+    }
+    // NOTE: the coefficients are kept modulo `m`, so that they do not need a signed type.
+    let (mut old_r, mut r) = (a % m, m);
+    let (mut old_s, mut s) = (1 % m, 0);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, submod(old_s, mulmod(q, s, m), m));
+    }
+
+    (old_r == 1).then_some(old_s)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The combined modulus of two congruences does not fit into a `u128`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the combined modulus overflows u128")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// The solutions of `x ≡ remainder (mod modulus)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    pub remainder: u128,
+    pub modulus: u128,
+}
+
+impl Congruence {
+    /// Creates a congruence, reducing the remainder. `modulus` must not be zero.
+    pub fn new(remainder: u128, modulus: u128) -> Self {
+        assert!(modulus > 0, "the modulus of a congruence must not be zero");
+        Congruence {
+            remainder: remainder % modulus,
+            modulus,
+        }
+    }
+
+    /// The congruence satisfied by exactly the numbers that satisfy both, or `None` if no number does. Fails if the
+    /// combined modulus does not fit into a `u128`.
+    pub fn merge(self, other: Congruence) -> Result<Option<Congruence>, Overflow> {
+        let g = gcd(self.modulus, other.modulus);
+        let diff = submod(other.remainder, self.remainder, other.modulus);
+        if !diff.is_multiple_of(g) {
+            return Ok(None);
+        }
+
+        let modulus = lcm(self.modulus, other.modulus).ok_or(Overflow)?;
+        // solve `self.modulus * k ≡ diff (mod other.modulus)` for `k`.
+        let reduced = other.modulus / g;
+        let k = match reduced {
+            1 => 0,
+            _ => mulmod(
+                diff / g,
+                modinv(self.modulus / g, reduced).expect("coprime after dividing by the gcd"),
+                reduced,
+            ),
+        };
+
+        Ok(Some(Congruence {
+            remainder: addmod(self.remainder, mulmod(self.modulus, k, modulus), modulus),
+            modulus,
+        }))
+    }
+}
+
+/// Generalized Chinese remainder theorem: the congruence satisfied by exactly the numbers that satisfy all of
+/// `congruences`, whose moduli do not need to be coprime. Returns `None` if no number satisfies all of them, and
+/// fails if their combined modulus does not fit into a `u128`.
+///
+/// ```
+/// use advent_of_code::number_theory::{crt, Congruence};
+/// let solution = crt([Congruence::new(2, 6), Congruence::new(8, 10)]).unwrap();
+/// assert_eq!(solution, Some(Congruence::new(8, 30)));
+/// ```
+pub fn crt(
+    congruences: impl IntoIterator<Item = Congruence>,
+) -> Result<Option<Congruence>, Overflow> {
+    let mut solution = Congruence::new(0, 1);
+    for congruence in congruences {
+        match solution.merge(congruence)? {
+            Some(merged) => solution = merged,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(solution))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An integer modulo `M`, e.g. `ModInt<20201227>`. `M` must not be zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(value: u64) -> Self {
+        ModInt(value % M)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn modulus(self) -> u64 {
+        M
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        self.with(powmod(self.0.into(), exp.into(), M.into()))
+    }
+
+    /// The multiplicative inverse, or `None` if the value is not coprime to `M`.
+    pub fn inv(self) -> Option<Self> {
+        modinv(self.0.into(), M.into()).map(|inverse| self.with(inverse))
+    }
+
+    fn with(self, value: u128) -> Self {
+        ModInt(value as u64)
+    }
+
+    fn check(self, _: Self) {}
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> Self {
+        ModInt::new(value)
+    }
+}
+
+/// An integer modulo a modulus that is only known at runtime. Both operands of an operator need the same modulus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DynModInt {
+    value: u64,
+    modulus: u64,
+}
+
+impl DynModInt {
+    pub fn new(value: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "the modulus must not be zero");
+        DynModInt {
+            value: value % modulus,
+            modulus,
+        }
+    }
+
+    pub fn value(self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(self) -> u64 {
+        self.modulus
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        self.with(powmod(self.value.into(), exp.into(), self.modulus.into()))
+    }
+
+    /// The multiplicative inverse, or `None` if the value is not coprime to the modulus.
+    pub fn inv(self) -> Option<Self> {
+        modinv(self.value.into(), self.modulus.into()).map(|inverse| self.with(inverse))
+    }
+
+    fn with(self, value: u128) -> Self {
+        DynModInt {
+            value: value as u64,
+            modulus: self.modulus,
+        }
+    }
+
+    fn check(self, rhs: Self) {
+        assert_eq!(
+            self.modulus, rhs.modulus,
+            "operands of modular arithmetic need the same modulus"
+        );
+    }
+}
+
+macro_rules! impl_mod_ops {
+    ($ty:ty $(, $m:ident)?) => {
+        impl$(<const $m: u64>)? Add for $ty {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                self.check(rhs);
+                self.with(addmod(self.value().into(), rhs.value().into(), self.modulus().into()))
+            }
+        }
+
+        impl$(<const $m: u64>)? Sub for $ty {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                self.check(rhs);
+                self.with(submod(self.value().into(), rhs.value().into(), self.modulus().into()))
+            }
+        }
+
+        impl$(<const $m: u64>)? Mul for $ty {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self {
+                self.check(rhs);
+                self.with(mulmod(self.value().into(), rhs.value().into(), self.modulus().into()))
+            }
+        }
+
+        /// Multiplies with the inverse of `rhs`.
+        ///
+        /// # Panics
+        /// If `rhs` is not coprime to the modulus.
+        impl$(<const $m: u64>)? Div for $ty {
+            type Output = Self;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn div(self, rhs: Self) -> Self {
+                self * rhs.inv().expect("the divisor has no inverse")
+            }
+        }
+
+        impl$(<const $m: u64>)? Neg for $ty {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                self.with(submod(0, self.value().into(), self.modulus().into()))
+            }
+        }
+
+        impl$(<const $m: u64>)? AddAssign for $ty {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl$(<const $m: u64>)? SubAssign for $ty {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl$(<const $m: u64>)? MulAssign for $ty {
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl$(<const $m: u64>)? DivAssign for $ty {
+            fn div_assign(&mut self, rhs: Self) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl$(<const $m: u64>)? Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.value())
+            }
+        }
+    };
+}
+
+impl_mod_ops!(ModInt<M>, M);
+impl_mod_ops!(DynModInt);
+
+/* -------------------------------------------------------------------------- */
+
+/// The smallest `x` with `base ^ x ≡ target (mod modulus)`, found with baby-step giant-step in `O(√modulus)`.
+/// `base` has to be coprime to `modulus`, returns `None` if it is not or if there is no such `x`.
+///
+/// ```
+/// // the loop size of the card in the example of 2020 day 25.
+/// assert_eq!(advent_of_code::number_theory::discrete_log(7, 5764801, 20201227), Some(8));
+/// ```
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    let m = u128::from(modulus);
+    let (base, target) = (u128::from(base) % m, u128::from(target) % m);
+    if target == 1 % m {
+        return Some(0);
+    }
+    if gcd(base, m) != 1 {
+        return None;
+    }
+
+    let n = (modulus as f64).sqrt().ceil() as u64 + 1;
+
+    // baby steps: `target * base ^ j` for `j < n`, keeping the largest `j` for the smallest `x`.
+    let mut baby_steps = HashMap::with_capacity(n as usize);
+    let mut value = target;
+    for j in 0..n {
+        baby_steps.insert(value, j);
+        value = mulmod(value, base, m);
+    }
+
+    // giant steps: `base ^ (i * n) = target * base ^ j` means `x = i * n - j`.
+    let giant = powmod(base, n.into(), m);
+    let mut value = giant;
+    for i in 1..=n {
+        if let Some(j) = baby_steps.get(&value) {
+            return Some(i * n - j);
+        }
+        value = mulmod(value, giant, m);
+    }
+    None
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        crt, discrete_log, egcd, gcd, lcm, modinv, mulmod, powmod, Congruence, DynModInt, ModInt,
+        Overflow,
+    };
+
+    #[test]
+    fn computes_divisors() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(u128::MAX, 2), None);
+
+        let (g, x, y) = egcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(egcd(-4, 6).0, 2);
+    }
+
+    #[test]
+    fn computes_inverses() {
+        assert_eq!(modinv(3, 11), Some(4));
+        assert_eq!(modinv(10, 17), Some(12));
+        assert_eq!(modinv(6, 9), None);
+        assert_eq!(modinv(5, 1), Some(0));
+
+        let m = u128::MAX - 158; // the largest prime below 2^128
+        let inverse = modinv(123_456_789, m).unwrap();
+        assert_eq!(mulmod(123_456_789, inverse, m), 1);
+    }
+
+    #[test]
+    fn does_not_overflow() {
+        let m = u128::MAX - 158;
+        assert_eq!(mulmod(m - 1, m - 1, m), 1);
+        assert_eq!(mulmod(u128::MAX, 2, 7), (u128::MAX % 7) * 2 % 7);
+        assert_eq!(powmod(2, 10, 1000), 24);
+        assert_eq!(powmod(7, 0, 1), 0);
+        // Fermat's little theorem.
+        assert_eq!(powmod(3, m - 1, m), 1);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        // the example of day 13: 7,13,x,x,59,x,31,19
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        let solution = crt(buses
+            .iter()
+            .map(|(id, offset)| Congruence::new((id - offset % id) % id, *id)))
+        .unwrap()
+        .unwrap();
+        assert_eq!(solution.remainder, 1068781);
+        assert_eq!(solution.modulus, 7 * 13 * 59 * 31 * 19);
+    }
+
+    #[test]
+    fn merges_non_coprime_moduli() {
+        assert_eq!(
+            crt([Congruence::new(2, 6), Congruence::new(8, 10)]),
+            Ok(Some(Congruence::new(8, 30)))
+        );
+        assert_eq!(
+            crt([Congruence::new(3, 4), Congruence::new(3, 8)]),
+            Ok(Some(Congruence::new(3, 8)))
+        );
+        assert_eq!(
+            crt([Congruence::new(1, 4), Congruence::new(2, 6)]),
+            Ok(None)
+        );
+        assert_eq!(crt([]), Ok(Some(Congruence::new(0, 1))));
+    }
+
+    #[test]
+    fn reports_overflowing_moduli() {
+        let large = Congruence::new(1, u128::MAX);
+        assert_eq!(large.merge(Congruence::new(0, 2)), Err(Overflow));
+        assert_eq!(
+            crt([large, Congruence::new(0, 2), Congruence::new(0, 3)]),
+            Err(Overflow)
+        );
+        // no solution is found before the moduli overflow.
+        assert_eq!(
+            crt([Congruence::new(0, 2), Congruence::new(1, 4), large]),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn computes_with_mod_ints() {
+        type M7 = ModInt<7>;
+        let (a, b) = (M7::new(5), M7::from(10));
+        assert_eq!(a + b, M7::new(1));
+        assert_eq!(b - a, M7::new(5));
+        assert_eq!(a * b, M7::new(1));
+        assert_eq!(-a, M7::new(2));
+        assert_eq!(a.inv(), Some(M7::new(3)));
+        assert_eq!((a / b) * b, a);
+        assert_eq!(a.pow(6), M7::new(1));
+        assert_eq!(ModInt::<6>::new(4).inv(), None);
+
+        let mut c = M7::new(3);
+        c *= c;
+        c += M7::new(5);
+        assert_eq!(c.to_string(), "0");
+
+        let big = ModInt::<{ u64::MAX }>::new(u64::MAX - 1);
+        assert_eq!((big * big).value(), 1);
+    }
+
+    #[test]
+    fn computes_with_runtime_moduli() {
+        let a = DynModInt::new(20, 13);
+        assert_eq!(a.value(), 7);
+        assert_eq!((a * a).value(), 10);
+        assert_eq!(a.inv().map(|inv| (inv * a).value()), Some(1));
+        assert_eq!(a.pow(12).value(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "same modulus")]
+    fn rejects_mixed_moduli() {
+        let _ = DynModInt::new(1, 13) + DynModInt::new(1, 7);
+    }
+
+    #[test]
+    fn finds_discrete_logs() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(2, 1, 11), Some(0));
+        assert_eq!(discrete_log(2, 3, 11), Some(8));
+        // 4 only generates 1, 4, 5, 9, 3 modulo 11.
+        assert_eq!(discrete_log(4, 2, 11), None);
+        assert_eq!(discrete_log(6, 4, 9), None);
+
+        // smallest solutions, as every power of 3 repeats after 4 steps modulo 5.
+        for x in 0..20 {
+            assert_eq!(
+                discrete_log(3, powmod(3, x, 5) as u64, 5),
+                Some(x as u64 % 4)
+            );
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Recognizes answers that puzzles draw as block letters, like the ones produced by "Transparent Origami" or
+//! "Cathode-Ray Tube".
+//!
+//! Two fonts show up in Advent of Code: a small one that is 6 pixels high (most letters are 4 wide) and a large one
+//! that is 10 pixels high (6 wide). Lit pixels are drawn as `#` (or `█`), everything else counts as dark.
+
+/* -------------------------------------------------------------------------- */
+
+const SMALL_HEIGHT: usize = 6;
+const LARGE_HEIGHT: usize = 10;
+
+const SMALL_GLYPHS: [(char, &str); 18] = [
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const LARGE_GLYPHS: [(char, &str); 15] = [
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/* -------------------------------------------------------------------------- */
+
+/// Decodes block letters drawn in `art` into a string.
+///
+/// Blank lines around the letters are ignored. Returns `None` if `art` is neither 6 nor 10 pixels high, or if it
+/// contains a shape that is not a known letter.
+///
+/// ```
+/// let art = "#..#.####\n#..#.#...\n####.###.\n#..#.#...\n#..#.#...\n#..#.####";
+/// assert_eq!(advent_of_code::ocr::parse_letters(art), Some("HE".into()));
+/// ```
+#[must_use]
+pub fn parse_letters(art: &str) -> Option<String> {
+    let rows: Vec<Vec<bool>> = art
+        .lines()
+        .map(|line| line.chars().map(|c| c == '#' || c == '█').collect())
+        .collect();
+
+    let first = rows.iter().position(|row| row.contains(&true))?;
+    let last = rows.iter().rposition(|row| row.contains(&true))?;
+    let rows = &rows[first..=last];
+
+    let glyphs: &[(char, &str)] = match rows.len() {
+        SMALL_HEIGHT => &SMALL_GLYPHS,
+        LARGE_HEIGHT => &LARGE_GLYPHS,
+        _ => return None,
+    };
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let is_lit = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or(false);
+    let is_empty_column = |x: usize| (0..rows.len()).all(|y| !is_lit(x, y));
+
+    let mut letters = String::new();
+    let mut x = 0;
+
+    while x < width {
+        if is_empty_column(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < width && !is_empty_column(x) {
+            x += 1;
+        }
+
+        let glyph = (0..rows.len())
+            .map(|y| {
+                (start..x)
+                    .map(|x| if is_lit(x, y) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (letter, _) = glyphs.iter().find(|(_, shape)| *shape == glyph)?;
+        letters.push(*letter);
+    }
+
+    Some(letters)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_letters, LARGE_GLYPHS, SMALL_GLYPHS};
+
+    #[test]
+    fn parses_every_small_glyph() {
+        for (letter, shape) in SMALL_GLYPHS {
+            assert_eq!(parse_letters(shape), Some(letter.to_string()));
+        }
+    }
+
+    #[test]
+    fn parses_every_large_glyph() {
+        for (letter, shape) in LARGE_GLYPHS {
+            assert_eq!(parse_letters(shape), Some(letter.to_string()));
+        }
+    }
+
+    #[test]
+    fn parses_small_words() {
+        let art = "
+###..#..#..##..#....####.###..#..#.####.
+#..#.#..#.#..#.#....#....#..#.#.#..#....
+#..#.#..#.#..#.#....###..#..#.##...###..
+###..#..#.####.#....#....###..#.#..#....
+#.#..#..#.#..#.#....#....#.#..#.#..#....
+#..#..##..#..#.####.####.#..#.#..#.####.
+";
+        assert_eq!(parse_letters(art), Some("RUALERKE".into()));
+    }
+
+    #[test]
+    fn parses_narrow_and_wide_small_glyphs() {
+        let art = "###.#...#\n.#..#...#\n.#...#.#.\n.#....#..\n.#....#..\n###...#..";
+        assert_eq!(parse_letters(art), Some("IY".into()));
+    }
+
+    #[test]
+    fn parses_large_words() {
+        let art = [
+            "#....#..######..",
+            "#....#..#.......",
+            ".#..#...#.......",
+            ".#..#...#.......",
+            "..##....#####...",
+            "..##....#.......",
+            ".#..#...#.......",
+            ".#..#...#.......",
+            "#....#..#.......",
+            "#....#..######..",
+        ]
+        .join("\n");
+        assert_eq!(parse_letters(&art), Some("XE".into()));
+    }
+
+    #[test]
+    fn parses_other_pixel_styles() {
+        let art = "█  █\n█  █\n████\n█  █\n█  █\n█  █";
+        assert_eq!(parse_letters(art), Some("H".into()));
+    }
+
+    #[test]
+    fn rejects_unknown_shapes() {
+        assert_eq!(parse_letters("#\n#\n#"), None);
+        assert_eq!(parse_letters("####\n####\n####\n####\n####\n####"), None);
+        assert_eq!(parse_letters("....\n...."), None);
+    }
+}
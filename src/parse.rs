@@ -0,0 +1,475 @@
+//! Helpers for the shapes that puzzle inputs come in: numbers, lists, blank-line separated groups, `key:value`
+//! records and `N-M c` policies.
+//!
+//! Errors are [`ParseError`]s that point at the offending text. Printed with `{:#}`, as the runner does for parts that
+//! return a `Result`, they show the line of the input with a caret underneath.
+
+use std::fmt::{self, Display};
+use std::num::{ParseFloatError, ParseIntError};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    position: Position,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Position {
+    Unknown,
+    /// Address and length of the offending text, until [`ParseError::locate`] finds it in the input.
+    Address(usize, usize),
+    Located(Location),
+}
+
+/// Where in the input a [`ParseError`] happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+    /// Number of characters that the error refers to, at least 1.
+    pub length: usize,
+    /// The line of the input that contains the error.
+    pub excerpt: String,
+}
+
+impl ParseError {
+    /// Creates an error about `at`, which has to be a part of the input for the error to be located.
+    pub fn new(message: impl Into<String>, at: &str) -> Self {
+        ParseError {
+            message: message.into(),
+            position: Position::Address(at.as_ptr() as usize, at.len()),
+        }
+    }
+
+    /// Creates an error without a position, e.g. about the input as a whole.
+    pub fn msg(message: impl Into<String>) -> Self {
+        ParseError {
+            message: message.into(),
+            position: Position::Unknown,
+        }
+    }
+
+    /// Converts the error of a `peg` grammar that parsed `input`.
+    pub fn from_peg(error: &peg::error::ParseError<peg::str::LineCol>, input: &str) -> Self {
+        let offset = error.location.offset.min(input.len());
+        // NOTE: the unexpected character can take up several bytes.
+        let length = input[offset..].chars().next().map_or(0, char::len_utf8);
+        ParseError::new(
+            format!("expected {}", error.expected),
+            &input[offset..offset + length],
+        )
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        match &self.position {
+            Position::Located(location) => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Finds the line and column of the error in `input`. Errors that are not about a part of `input` are returned
+    /// unchanged.
+    pub fn locate(self, input: &str) -> Self {
+        let Position::Address(address, length) = self.position else {
+            return self;
+        };
+        // NOTE: only compares addresses, so that errors can be created from any slice without a lifetime.
+        let Some(offset) = address
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| offset + length <= input.len())
+        else {
+            return self;
+        };
+
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let length = input[offset..(offset + length).min(line_end)]
+            .chars()
+            .count();
+
+        ParseError {
+            position: Position::Located(Location {
+                line: input[..offset].matches('\n').count() + 1,
+                column: input[line_start..offset].chars().count() + 1,
+                length: length.max(1),
+                excerpt: input[line_start..line_end]
+                    .trim_end_matches('\r')
+                    .to_string(),
+            }),
+            ..self
+        }
+    }
+
+    /// Points the error at `line`, unless it already points at a part of it.
+    fn on_line(self, line: &str) -> Self {
+        match self.position {
+            Position::Unknown => ParseError::new(self.message, line),
+            _ => self,
+        }
+    }
+}
+
+/// Prints `line 2, column 5: message`, or with `{:#}` the excerpt with a caret under the error.
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(location) = self.location() else {
+            return write!(f, "{}", self.message);
+        };
+
+        if !f.alternate() {
+            return write!(
+                f,
+                "line {}, column {}: {}",
+                location.line, location.column, self.message
+            );
+        }
+
+        let number = location.line.to_string();
+        let gutter = " ".repeat(number.len());
+        writeln!(f, "{}", self.message)?;
+        writeln!(
+            f,
+            "{gutter}--> line {}, column {}",
+            location.line, location.column
+        )?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{number} | {}", location.excerpt)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(location.column - 1),
+            "^".repeat(location.length)
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<String> for ParseError {
+    fn from(message: String) -> Self {
+        ParseError::msg(message)
+    }
+}
+
+impl From<&str> for ParseError {
+    fn from(message: &str) -> Self {
+        ParseError::msg(message)
+    }
+}
+
+impl From<ParseIntError> for ParseError {
+    fn from(error: ParseIntError) -> Self {
+        ParseError::msg(error.to_string())
+    }
+}
+
+impl From<ParseFloatError> for ParseError {
+    fn from(error: ParseFloatError) -> Self {
+        ParseError::msg(error.to_string())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Parses an unsigned integer, surrounding whitespace is ignored.
+pub fn unsigned(s: &str) -> Result<u64, ParseError> {
+    let s = s.trim();
+    let error = || ParseError::new(format!("expected an unsigned integer, found `{s}`"), s);
+    if !s.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(error());
+    }
+    s.parse().map_err(|_| error())
+}
+
+/// Parses an integer with an optional `+` or `-` sign, surrounding whitespace is ignored.
+pub fn signed(s: &str) -> Result<i64, ParseError> {
+    let s = s.trim();
+    s.parse()
+        .map_err(|_| ParseError::new(format!("expected an integer, found `{s}`"), s))
+}
+
+/// All integers in `s`, wherever they are. A `-` directly in front of digits makes them negative, unless it follows
+/// another digit, so that `1-3` yields `1` and `3`.
+pub fn ints(s: &str) -> Vec<i64> {
+    let bytes = s.as_bytes();
+    let mut numbers = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let negative = start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_digit());
+
+        // NOTE: numbers too large for an `i64` are skipped rather than wrapped.
+        if let Ok(n) = s[start..i].parse::<i64>() {
+            numbers.push(if negative { -n } else { n });
+        }
+    }
+    numbers
+}
+
+/// All unsigned integers in `s`, signs are ignored.
+pub fn uints(s: &str) -> Vec<u64> {
+    s.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+/// Groups of lines separated by one or more blank lines. Leading and trailing blank lines are ignored.
+pub fn groups(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    let line_end = |s: &str, start: usize| s[start..].find('\n').map_or(s.len(), |i| start + i + 1);
+
+    std::iter::from_fn(move || {
+        while !rest.is_empty() && rest[..line_end(rest, 0)].trim().is_empty() {
+            rest = &rest[line_end(rest, 0)..];
+        }
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut end = 0;
+        while end < rest.len() && !rest[end..line_end(rest, end)].trim().is_empty() {
+            end = line_end(rest, end);
+        }
+
+        let group = rest[..end].trim_end();
+        rest = &rest[end..];
+        Some(group)
+    })
+}
+
+/// Whitespace separated `key:value` pairs, in the order they appear.
+pub fn records(s: &str) -> Result<Vec<(&str, &str)>, ParseError> {
+    s.split_whitespace()
+        .map(|field| {
+            field.split_once(':').ok_or_else(|| {
+                ParseError::new(format!("expected `key:value`, found `{field}`"), field)
+            })
+        })
+        .collect()
+}
+
+/// Parses an inclusive range written as `N-M`.
+pub fn range(s: &str) -> Result<RangeInclusive<u64>, ParseError> {
+    let s = s.trim();
+    let (start, end) = s
+        .split_once('-')
+        .ok_or_else(|| ParseError::new(format!("expected a range `N-M`, found `{s}`"), s))?;
+    Ok(unsigned(start)?..=unsigned(end)?)
+}
+
+/// Parses a range followed by a single character, e.g. the `1-3 a` of a password policy.
+pub fn range_char(s: &str) -> Result<(RangeInclusive<u64>, char), ParseError> {
+    let s = s.trim();
+    let (range_part, c) = s
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(format!("expected `N-M c`, found `{s}`"), s))?;
+
+    let mut chars = c.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), None) => Ok((range(range_part)?, first)),
+        _ => Err(ParseError::new(
+            format!("expected a single character, found `{c}`"),
+            c,
+        )),
+    }
+}
+
+/// Parses a comma separated list, whitespace around the items is ignored.
+pub fn comma_list<T>(s: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.split(',')
+        .map(|item| {
+            let item = item.trim();
+            item.parse()
+                .map_err(|e| ParseError::new(format!("invalid list item `{item}`: {e}"), item))
+        })
+        .collect()
+}
+
+/// Parses every line of `input` with [`FromStr`]. Errors are located in `input`, errors without a position point at
+/// the whole line.
+pub fn lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Into<ParseError>,
+{
+    input
+        .lines()
+        .map(|line| {
+            line.parse()
+                .map_err(|e: T::Err| e.into().on_line(line).locate(input))
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        comma_list, groups, ints, lines, range, range_char, records, signed, uints, unsigned,
+        Location, ParseError,
+    };
+
+    #[test]
+    fn parses_integers() {
+        assert_eq!(unsigned(" 42\n"), Ok(42));
+        assert!(unsigned("+42").is_err());
+        assert!(unsigned("-1").is_err());
+        assert_eq!(signed("+7"), Ok(7));
+        assert_eq!(signed("-99"), Ok(-99));
+        assert_eq!(
+            signed("x").unwrap_err().message(),
+            "expected an integer, found `x`"
+        );
+    }
+
+    #[test]
+    fn finds_all_integers() {
+        assert_eq!(ints("pos=<-1,20,3>, r=-4"), vec![-1, 20, 3, -4]);
+        assert_eq!(ints("1-3 a"), vec![1, 3]);
+        assert_eq!(uints("mem[8] = 11"), vec![8, 11]);
+        assert_eq!(uints("x=-5"), vec![5]);
+        assert!(ints("no numbers").is_empty());
+    }
+
+    #[test]
+    fn splits_groups() {
+        let input = "\nabc\n\na\nb\r\n\r\n\n\nac\n\n";
+        assert_eq!(groups(input).collect::<Vec<_>>(), vec!["abc", "a\nb", "ac"]);
+        assert_eq!(groups("").count(), 0);
+        assert_eq!(groups("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn parses_records() {
+        assert_eq!(
+            records("ecl:gry pid:860033327\nbyr:1937"),
+            Ok(vec![("ecl", "gry"), ("pid", "860033327"), ("byr", "1937")])
+        );
+        assert!(records("ecl:gry pid").is_err());
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(range("1-3"), Ok(1..=3));
+        assert!(range("1..3").is_err());
+        assert_eq!(range_char("2-9 c"), Ok((2..=9, 'c')));
+        assert!(range_char("2-9 cc").is_err());
+        assert!(range_char("2-9").is_err());
+    }
+
+    #[test]
+    fn parses_lists_and_lines() {
+        assert_eq!(comma_list::<u8>("7, 13,17"), Ok(vec![7, 13, 17]));
+        assert!(comma_list::<u8>("7,x")
+            .unwrap_err()
+            .message()
+            .contains("`x`"));
+        assert_eq!(lines::<i32>("1\n-2\n"), Ok(vec![1, -2]));
+        assert_eq!(
+            lines::<i32>("1\nfoo").unwrap_err().to_string(),
+            "line 2, column 1: invalid digit found in string"
+        );
+    }
+
+    #[derive(Debug)]
+    struct Policy;
+
+    impl std::str::FromStr for Policy {
+        type Err = ParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (policy, _) = s
+                .split_once(": ")
+                .ok_or_else(|| ParseError::new("expected `: `", s))?;
+            range_char(policy).map(|_| Policy)
+        }
+    }
+
+    #[test]
+    fn locates_errors() {
+        let error = lines::<Policy>("1-3 a: abcde\n1-x b: cdefg\n").unwrap_err();
+        assert_eq!(
+            error.location(),
+            Some(&Location {
+                line: 2,
+                column: 3,
+                length: 1,
+                excerpt: "1-x b: cdefg".into()
+            })
+        );
+        assert_eq!(
+            format!("{error:#}"),
+            "expected an unsigned integer, found `x`\n --> line 2, column 3\n  |\n2 | 1-x b: cdefg\n  |   ^"
+        );
+
+        let error = lines::<Policy>("1-3 a: abcde\n1-3 bc: cdefg").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: expected a single character, found `bc`"
+        );
+        assert_eq!(error.location().map(|l| l.length), Some(2));
+    }
+
+    #[test]
+    fn keeps_errors_outside_of_the_input() {
+        let error = ParseError::new("unexpected", "elsewhere").locate("input");
+        assert_eq!(error.location(), None);
+        assert_eq!(format!("{error:#}"), "unexpected");
+    }
+
+    #[test]
+    fn converts_peg_errors() {
+        peg::parser! {
+            grammar number() for str {
+                pub rule number() -> u64 = n:$(['0'..='9']+) {? n.parse().or(Err("u64")) }
+            }
+        }
+
+        let input = "12\n3a";
+        let error = input
+            .lines()
+            .map(|line| number::number(line).map_err(|e| ParseError::from_peg(&e, line)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err()
+            .locate(input);
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 2: expected one of EOF, ['0'..='9']"
+        );
+
+        let input = "1\n3é";
+        let error = number::number(&input[2..])
+            .map_err(|e| ParseError::from_peg(&e, &input[2..]))
+            .unwrap_err()
+            .locate(input);
+        assert_eq!(
+            error.location().map(|l| (l.line, l.column, l.length)),
+            Some((2, 2, 1))
+        );
+    }
+}
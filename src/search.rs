@@ -0,0 +1,323 @@
+//! Shortest paths through state spaces that are only known through a successor function, e.g. positions in a maze.
+//!
+//! [`dijkstra`] and [`astar`] work with any non-negative costs, [`bfs_01`] only with costs of `0` and `1` but without
+//! a priority queue. All of them stop as soon as a goal state is taken off the queue, and return a [`Path`].
+//!
+//! [`grid_successors_4`] and [`grid_successors_8`] turn a [`Grid`] into a successor function over positions.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use crate::grid::Grid;
+
+/// A position on a [`Grid`], as `(x, y)`.
+type Position = (usize, usize);
+
+/// Costs of moving between states. Implemented for all types that can be added up and compared, with
+/// `Default::default()` as the cost of not moving.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/// A cheapest path to a goal, found by one of the searches.
+#[derive(Clone, Debug)]
+pub struct Path<S, C> {
+    pub cost: C,
+    /// The states along the path, from the start to the goal, both included.
+    pub states: Vec<S>,
+    /// All states whose cheapest cost was known when the goal was reached.
+    pub visited: HashSet<S>,
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// States seen so far, with the best known cost and the state it was reached from.
+struct Explored<S, C> {
+    states: Vec<S>,
+    ids: HashMap<S, usize>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>,
+    done: Vec<bool>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Explored<S, C> {
+    fn new(start: S) -> Self {
+        Explored {
+            ids: HashMap::from([(start.clone(), 0)]),
+            states: vec![start],
+            costs: vec![C::default()],
+            parents: vec![None],
+            done: vec![false],
+        }
+    }
+
+    /// Records that `state` can be reached from `parent` for `cost`. Returns its id if that is cheaper than before.
+    fn relax(&mut self, state: S, cost: C, parent: usize) -> Option<usize> {
+        match self.ids.get(&state) {
+            Some(&id) if self.done[id] || self.costs[id] <= cost => None,
+            Some(&id) => {
+                self.costs[id] = cost;
+                self.parents[id] = Some(parent);
+                Some(id)
+            }
+            None => {
+                let id = self.states.len();
+                self.ids.insert(state.clone(), id);
+                self.states.push(state);
+                self.costs.push(cost);
+                self.parents.push(Some(parent));
+                self.done.push(false);
+                Some(id)
+            }
+        }
+    }
+
+    fn into_path(self, goal: usize) -> Path<S, C> {
+        let mut ids = vec![goal];
+        while let Some(parent) = self.parents[*ids.last().unwrap()] {
+            ids.push(parent);
+        }
+
+        Path {
+            cost: self.costs[goal],
+            states: ids
+                .into_iter()
+                .rev()
+                .map(|id| self.states[id].clone())
+                .collect(),
+            visited: self
+                .states
+                .into_iter()
+                .zip(self.done)
+                .filter_map(|(state, done)| done.then_some(state))
+                .collect(),
+        }
+    }
+}
+
+/// Finds a cheapest path from `start` to any state for which `is_goal` returns `true`. `successors` returns the
+/// states that can be reached from a state, and what it costs to get there. Returns `None` if no goal is reachable.
+///
+/// ```
+/// # use advent_of_code::search::dijkstra;
+/// // from 1 to 10 by adding one for a cost of 1, or doubling for a cost of 2.
+/// let path = dijkstra(1u64, |n| [(n + 1, 1), (n * 2, 2)], |n| *n == 10).unwrap();
+/// assert_eq!(path.cost, 6);
+/// ```
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, is_goal, |_| C::default())
+}
+
+/// Like [`dijkstra`], but explores states in the order of their cost plus `heuristic`, an estimate of the remaining
+/// cost to a goal. States are not explored again once they are done, so the path is only guaranteed to be the
+/// cheapest if the heuristic is consistent: it never drops by more than the cost of a move,
+/// `h(a) <= cost(a, b) + h(b)`, and is `0` at the goals.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+    mut heuristic: impl FnMut(&S) -> C,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut explored = Explored::new(start);
+
+    while let Some(Reverse((_, cost, id))) = queue.pop() {
+        // NOTE: a state is queued again whenever a cheaper way to it is found, skip the outdated entries.
+        if explored.done[id] || explored.costs[id] < cost {
+            continue;
+        }
+        explored.done[id] = true;
+
+        let state = explored.states[id].clone();
+        if is_goal(&state) {
+            return Some(explored.into_path(id));
+        }
+
+        for (next, step) in successors(&state) {
+            let estimate = heuristic(&next);
+            if let Some(next) = explored.relax(next, cost + step, id) {
+                queue.push(Reverse((cost + step + estimate, cost + step, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Like [`dijkstra`], for moves that cost either `0` or `1`. Free moves are explored first, so a deque is enough
+/// to keep the states ordered by cost.
+pub fn bfs_01<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, u64>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut queue = VecDeque::from([(0, 0)]);
+    let mut explored = Explored::new(start);
+
+    while let Some((cost, id)) = queue.pop_front() {
+        if explored.done[id] || explored.costs[id] < cost {
+            continue;
+        }
+        explored.done[id] = true;
+
+        let state = explored.states[id].clone();
+        if is_goal(&state) {
+            return Some(explored.into_path(id));
+        }
+
+        for (next, step) in successors(&state) {
+            assert!(
+                step <= 1,
+                "a 0-1 BFS only supports costs of 0 and 1, got {step}"
+            );
+            if let Some(next) = explored.relax(next, cost + step, id) {
+                match step {
+                    0 => queue.push_front((cost, next)),
+                    _ => queue.push_back((cost + 1, next)),
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Successors of a position on `grid`: the orthogonal neighbours for which `cost` returns the cost of entering them
+/// from the current cell. Neighbours for which it returns `None`, e.g. walls, cannot be entered.
+///
+/// ```
+/// # use advent_of_code::grid::Grid;
+/// # use advent_of_code::search::{dijkstra, grid_successors_4};
+/// let maze = Grid::parse("..#\n#..\n...").unwrap();
+/// let path = dijkstra((0, 0), grid_successors_4(&maze, |_, to| (*to != '#').then_some(1)), |pos| *pos == (2, 2));
+/// assert_eq!(path.unwrap().cost, 4);
+/// ```
+pub fn grid_successors_4<'a, T, C>(
+    grid: &'a Grid<T>,
+    cost: impl Fn(&T, &T) -> Option<C> + 'a,
+) -> impl FnMut(&Position) -> Vec<(Position, C)> + 'a {
+    move |&(x, y)| {
+        grid.neighbours_4(x, y)
+            .filter_map(|next| Some((next, cost(&grid[(x, y)], &grid[next])?)))
+            .collect()
+    }
+}
+
+/// Like [`grid_successors_4`], but also moves diagonally.
+pub fn grid_successors_8<'a, T, C>(
+    grid: &'a Grid<T>,
+    cost: impl Fn(&T, &T) -> Option<C> + 'a,
+) -> impl FnMut(&Position) -> Vec<(Position, C)> + 'a {
+    move |&(x, y)| {
+        grid.neighbours_8(x, y)
+            .filter_map(|next| Some((next, cost(&grid[(x, y)], &grid[next])?)))
+            .collect()
+    }
+}
+
+/// Heuristic for [`astar`] on a grid with orthogonal moves that cost at least `1`.
+pub fn manhattan_to(goal: Position) -> impl Fn(&Position) -> u64 {
+    move |&(x, y)| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u64
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{astar, bfs_01, dijkstra, grid_successors_4, grid_successors_8, manhattan_to};
+    use crate::grid::Grid;
+
+    /// The risk levels of the example of 2021 day 15, where entering a cell costs its digit.
+    const CAVE: &str = "\
+1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+    fn risk(_: &char, to: &char) -> Option<u64> {
+        to.to_digit(10).map(u64::from)
+    }
+
+    #[test]
+    fn finds_cheapest_paths() {
+        let cave = Grid::parse(CAVE).unwrap();
+        let goal = (cave.width() - 1, cave.height() - 1);
+
+        let path = dijkstra((0, 0), grid_successors_4(&cave, risk), |pos| *pos == goal).unwrap();
+        assert_eq!(path.cost, 40);
+        assert_eq!(path.states.first(), Some(&(0, 0)));
+        assert_eq!(path.states.last(), Some(&goal));
+        assert_eq!(
+            path.states[1..]
+                .iter()
+                .map(|pos| risk(&'0', &cave[*pos]).unwrap())
+                .sum::<u64>(),
+            40
+        );
+
+        let guided = astar(
+            (0, 0),
+            grid_successors_4(&cave, risk),
+            |pos| *pos == goal,
+            manhattan_to(goal),
+        )
+        .unwrap();
+        assert_eq!(guided.cost, 40);
+        assert!(guided.visited.len() <= path.visited.len());
+        assert!(guided.visited.contains(&goal));
+    }
+
+    #[test]
+    fn moves_diagonally() {
+        let maze = Grid::parse(".#.\n#.#\n.#.").unwrap();
+        let open = |_: &char, to: &char| (*to == '.').then_some(1u64);
+
+        assert!(dijkstra((0, 0), grid_successors_4(&maze, open), |pos| *pos == (2, 2)).is_none());
+        let path = dijkstra((0, 0), grid_successors_8(&maze, open), |pos| *pos == (2, 2)).unwrap();
+        assert_eq!(path.states, vec![(0, 0), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn searches_with_free_moves() {
+        // from 1 to a goal by doubling for free or adding one for a cost of 1.
+        let successors = |n: &u64| {
+            [(n * 2, 0), (n + 1, 1)]
+                .into_iter()
+                .filter(|(n, _)| *n <= 100)
+        };
+
+        let path = bfs_01(1, successors, |n| *n == 100).unwrap();
+        assert_eq!(path.cost, 2);
+        assert_eq!(path.states, vec![1, 2, 3, 6, 12, 24, 25, 50, 100]);
+        assert_eq!(dijkstra(1, successors, |n| *n == 100).unwrap().cost, 2);
+        assert!(bfs_01(1, successors, |n| *n == 0).is_none());
+    }
+}
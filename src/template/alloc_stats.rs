@@ -0,0 +1,218 @@
+/// A lightweight global allocator that counts allocations, enabled by the `alloc-stats` feature.
+/// Unlike `--dhat`, this runs with the regular profiles and records every solution part.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator wrapping [`System`] that keeps track of live, peak and total allocated bytes.
+pub struct CountingAlloc;
+
+fn record_alloc(size: usize) {
+    COUNT.fetch_add(1, Ordering::Relaxed);
+    TOTAL.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    CURRENT.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Run `func` and return its result together with the allocations it made.
+/// Only meaningful if [`CountingAlloc`] is installed as the global allocator.
+pub fn measure<T>(func: impl FnOnce() -> T) -> (T, AllocStats) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    TOTAL.store(0, Ordering::Relaxed);
+    COUNT.store(0, Ordering::Relaxed);
+
+    let result = func();
+
+    let stats = AllocStats {
+        peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(baseline),
+        total_bytes: TOTAL.load(Ordering::Relaxed),
+        allocations: COUNT.load(Ordering::Relaxed),
+    };
+
+    (result, stats)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Allocations made by a single run of a solution part.
+///
+/// # Display
+/// This value displays in a human readable format that can be parsed again.
+///
+/// ```
+/// # use advent_of_code::template::alloc_stats::AllocStats;
+/// let stats = AllocStats { peak_bytes: 1536, total_bytes: 4096, allocations: 12 };
+/// assert_eq!(stats.to_string(), "1.5 KiB peak, 4.0 KiB total, 12 allocs")
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Highest number of bytes that were live at the same time, on top of what was live before.
+    pub peak_bytes: usize,
+    /// Sum of the sizes of all allocations.
+    pub total_bytes: usize,
+    /// Number of allocations.
+    pub allocations: usize,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} peak, {} total, {} allocs",
+            format_bytes(self.peak_bytes),
+            format_bytes(self.total_bytes),
+            self.allocations
+        )
+    }
+}
+
+impl FromStr for AllocStats {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(", ");
+        let mut next = |suffix: &str| {
+            parts
+                .next()
+                .and_then(|p| p.trim().strip_suffix(suffix))
+                .ok_or_else(|| format!("expected allocation stats, found `{s}`."))
+        };
+
+        let peak_bytes = parse_bytes(next(" peak")?)?;
+        let total_bytes = parse_bytes(next(" total")?)?;
+        let allocations = next(" allocs")?
+            .parse()
+            .map_err(|_| format!("invalid allocation count in `{s}`."))?;
+
+        Ok(AllocStats {
+            peak_bytes,
+            total_bytes,
+            allocations,
+        })
+    }
+}
+
+const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: usize) -> String {
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    format!("{value:.1} {unit}")
+}
+
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap
+)]
+fn parse_bytes(s: &str) -> Result<usize, String> {
+    let err = || format!("invalid byte size `{s}`.");
+    let (value, unit) = s.split_once(' ').ok_or_else(err)?;
+
+    if unit == "B" {
+        return value.parse().map_err(|_| err());
+    }
+
+    let exp = UNITS.iter().position(|u| *u == unit).ok_or_else(err)? + 1;
+    let value: f64 = value.parse().map_err(|_| err())?;
+    Ok((value * 1024_f64.powi(exp as i32)).round() as usize)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::AllocStats;
+
+    #[test]
+    fn formats_stats() {
+        let stats = AllocStats {
+            peak_bytes: 512,
+            total_bytes: 3 * 1024 * 1024,
+            allocations: 3,
+        };
+        assert_eq!(stats.to_string(), "512 B peak, 3.0 MiB total, 3 allocs");
+    }
+
+    #[test]
+    fn parses_stats() {
+        let stats: AllocStats = "1.5 KiB peak, 2.0 GiB total, 17 allocs".parse().unwrap();
+        assert_eq!(stats.peak_bytes, 1536);
+        assert_eq!(stats.total_bytes, 2 * 1024 * 1024 * 1024);
+        assert_eq!(stats.allocations, 17);
+    }
+
+    #[test]
+    fn roundtrips_small_stats() {
+        let stats = AllocStats {
+            peak_bytes: 0,
+            total_bytes: 1000,
+            allocations: 0,
+        };
+        assert_eq!(stats.to_string().parse::<AllocStats>(), Ok(stats));
+    }
+
+    #[test]
+    fn rejects_malformed_stats() {
+        assert!("1.5 KiB peak".parse::<AllocStats>().is_err());
+        assert!("1.5 KB peak, 1 B total, 1 allocs"
+            .parse::<AllocStats>()
+            .is_err());
+    }
+}
@@ -1,10 +1,15 @@
 /// Wrapper module around the "aoc-cli" command-line.
 use std::{
+    env,
     fmt::Display,
+    fs,
+    path::{Path, PathBuf},
     process::{Command, Output, Stdio},
 };
 
-use crate::template::Day;
+use crate::template::encryption::{self, Key};
+use crate::template::journal::{self, Event, Kind, Outcome};
+use crate::template::{config, Day};
 
 #[derive(Debug)]
 pub enum AocCommandError {
@@ -33,41 +38,52 @@ pub fn check() -> Result<(), AocCommandError> {
     Ok(())
 }
 
-pub fn read(day: Day) -> Result<Output, AocCommandError> {
-    let puzzle_path = get_puzzle_path(day);
-
-    let args = build_args(
-        "read",
-        &[
-            "--description-only".into(),
-            "--puzzle-file".into(),
-            puzzle_path,
-        ],
-        day,
-    );
-
-    call_aoc_cli(&args)
-}
-
 pub fn download(day: Day) -> Result<Output, AocCommandError> {
     let input_path = get_input_path(day);
     let puzzle_path = get_puzzle_path(day);
 
+    // NOTE: the input is downloaded outside of the repository, so that a plaintext input never lands in `data/inputs`
+    // when it is encrypted.
+    let download_path = env::temp_dir().join(format!("aoc-input-{day}-{}.txt", std::process::id()));
+
     let args = build_args(
         "download",
         &[
             "--overwrite".into(),
             "--input-file".into(),
-            input_path.to_string(),
+            download_path.to_string_lossy().into_owned(),
             "--puzzle-file".into(),
             puzzle_path.to_string(),
         ],
         day,
     );
 
-    let output = call_aoc_cli(&args)?;
+    let output = call_aoc_cli(&args, Stdio::inherit());
+    let input = fs::read_to_string(&download_path);
+    let _ = fs::remove_file(&download_path);
+    let output = output?;
+    journal::record(&Event::now(day, Kind::Download));
+
+    let input = match input {
+        Ok(input) if config::get().encryption.enabled => match Key::load() {
+            Ok(key) => Ok(encryption::encrypt(&input, &key)),
+            Err(e) => Err(format!("it could not be encrypted, {e}")),
+        },
+        Ok(input) => Ok(input),
+        Err(e) => Err(e.to_string()),
+    };
+
     println!("---");
-    println!("🎄 Successfully wrote input to \"{}\".", &input_path);
+    match input.and_then(|input| fs::write(&input_path, input).map_err(|e| e.to_string())) {
+        Ok(()) if config::get().encryption.enabled => {
+            println!(
+                "🔒 Successfully wrote encrypted input to \"{}\".",
+                &input_path
+            );
+        }
+        Ok(()) => println!("🎄 Successfully wrote input to \"{}\".", &input_path),
+        Err(e) => eprintln!("Failed to write input to \"{input_path}\": {e}"),
+    }
     println!("🎄 Successfully wrote puzzle to \"{}\".", &puzzle_path);
     Ok(output)
 }
@@ -77,42 +93,87 @@ pub fn submit(day: Day, part: u8, result: &str) -> Result<Output, AocCommandErro
     let mut args = build_args("submit", &[], day);
     args.push(part.to_string());
     args.push(result.to_string());
-    call_aoc_cli(&args)
+
+    // the response is captured to journal the outcome of the submission, and printed afterwards.
+    let output = call_aoc_cli(&args, Stdio::piped());
+    if let Ok(output) | Err(AocCommandError::BadExitStatus(output)) = &output {
+        let response = String::from_utf8_lossy(&output.stdout);
+        print!("{response}");
+        journal::record(&Event::now(
+            day,
+            Kind::Submit {
+                part,
+                answer: result.to_string(),
+                outcome: Outcome::from_response(&response),
+            },
+        ));
+    }
+    output
 }
 
-fn get_input_path(day: Day) -> String {
-    format!("data/inputs/{day}.txt")
+/// Reads the session token like aoc-cli does for downloads: from the configured session file, the
+/// `ADVENT_OF_CODE_SESSION` environment variable or `.adventofcode.session` in the home or config directory.
+pub fn session_token() -> Option<String> {
+    if let Some(path) = &config::get().session_file {
+        return read_token(path);
+    }
+
+    if let Some(token) = env::var("ADVENT_OF_CODE_SESSION")
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+    {
+        return Some(token.trim().to_string());
+    }
+
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)?;
+    let config_dir =
+        env::var_os("XDG_CONFIG_HOME").map_or_else(|| home.join(".config"), PathBuf::from);
+
+    read_token(&home.join(".adventofcode.session"))
+        .or_else(|| read_token(&config_dir.join("adventofcode.session")))
 }
 
-fn get_puzzle_path(day: Day) -> String {
-    format!("data/puzzles/{day}.md")
+fn read_token(path: &Path) -> Option<String> {
+    let token = fs::read_to_string(path).ok()?;
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
 }
 
-fn get_year() -> Option<u16> {
-    match std::env::var("AOC_YEAR") {
-        Ok(x) => x.parse().ok().or(None),
-        Err(_) => None,
-    }
+fn get_input_path(day: Day) -> String {
+    config::get().input_path(day).display().to_string()
+}
+
+fn get_puzzle_path(day: Day) -> String {
+    config::get().puzzle_path(day).display().to_string()
 }
 
 fn build_args(command: &str, args: &[String], day: Day) -> Vec<String> {
     let mut cmd_args = args.to_vec();
 
-    if let Some(year) = get_year() {
+    let config = config::get();
+
+    if let Some(year) = config.year {
         cmd_args.push("--year".into());
         cmd_args.push(year.to_string());
     }
 
+    if let Some(session_file) = &config.session_file {
+        cmd_args.push("--session-file".into());
+        cmd_args.push(session_file.display().to_string());
+    }
+
     cmd_args.append(&mut vec!["--day".into(), day.to_string(), command.into()]);
 
     cmd_args
 }
 
-fn call_aoc_cli(args: &[String]) -> Result<Output, AocCommandError> {
+fn call_aoc_cli(args: &[String], stdout: Stdio) -> Result<Output, AocCommandError> {
     // println!("Calling >aoc with: {}", args.join(" "));
     let output = Command::new("aoc")
         .args(args)
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .output()
         .map_err(|_| AocCommandError::CommandNotCallable)?;
@@ -0,0 +1,159 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+use crate::template::encryption::{self, Key, KEY_VARIABLE};
+use crate::template::{all_days, config};
+
+/// Creates a new key file, to be shared with everyone that should be able to read the inputs.
+pub fn keygen() {
+    let path = &config::get().encryption.key_file;
+
+    let key = Key::generate().unwrap_or_else(|e| {
+        eprintln!(
+            "Failed to generate a key: {e}. Create one with e.g. `openssl rand -hex 32` instead."
+        );
+        process::exit(1);
+    });
+
+    let file = OpenOptions::new().write(true).create_new(true).open(path);
+    match file.and_then(|mut file| writeln!(file, "{}", key.to_hex())) {
+        Ok(()) => {
+            println!("Created key file \"{}\".", path.display());
+            println!("---");
+            println!("🔑 Keep it out of the repository and share it privately, or set `{KEY_VARIABLE}` in CI.");
+        }
+        Err(e) => {
+            eprintln!("Failed to create key file \"{}\": {e}", path.display());
+            process::exit(1);
+        }
+    }
+}
+
+/// Encrypts all plaintext inputs in place.
+pub fn encrypt() {
+    let key = load_key();
+    let mut count = 0;
+
+    for day in all_days() {
+        let path = config::get().input_path(day);
+        if !path.exists() {
+            continue;
+        }
+        match encryption::encrypt_file(&path, &key) {
+            Ok(true) => {
+                println!("Encrypted \"{}\".", path.display());
+                count += 1;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("Failed to encrypt \"{}\": {e}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+
+    println!("🔒 Encrypted {count} input(s).");
+}
+
+/// Fails if plaintext inputs are staged for a commit. Meant to be run as a git pre-commit hook.
+pub fn check() {
+    let output = Command::new("git")
+        .args([
+            "diff",
+            "--cached",
+            "--name-only",
+            "--diff-filter=ACMR",
+            "-z",
+        ])
+        .output();
+
+    let staged = match output {
+        Ok(output) if output.status.success() => output.stdout,
+        _ => {
+            eprintln!("Failed to list the staged files, is this a git repository?");
+            process::exit(1);
+        }
+    };
+
+    let plaintext: Vec<PathBuf> = staged
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| PathBuf::from(String::from_utf8_lossy(path).to_string()))
+        .filter(|path| is_input(path))
+        .filter(|path| staged_content(path).is_some_and(|content| is_plaintext(&content)))
+        .collect();
+
+    if plaintext.is_empty() {
+        println!("🔒 No plaintext inputs are staged.");
+        return;
+    }
+
+    eprintln!("Plaintext inputs are staged for commit:");
+    for path in &plaintext {
+        eprintln!("  {}", path.display());
+    }
+    eprintln!("Run \"cargo inputs encrypt\" and stage them again.");
+    process::exit(1);
+}
+
+fn load_key() -> Key {
+    Key::load().unwrap_or_else(|e| {
+        eprintln!("{e}. Run \"cargo inputs keygen\" to create a key.");
+        process::exit(1);
+    })
+}
+
+/// Whether a path, relative to the repository root, is the input of a day.
+fn is_input(path: &Path) -> bool {
+    let canonical = |dir: &Path| fs::canonicalize(dir).unwrap_or(dir.to_path_buf());
+
+    path.extension().is_some_and(|extension| extension == "txt")
+        && path
+            .parent()
+            .is_some_and(|dir| canonical(dir) == canonical(&config::get().inputs_dir))
+}
+
+/// The content of a file as it is staged, which may differ from the working tree.
+fn staged_content(path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("show")
+        .arg(format!(":{}", path.display()))
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Empty inputs, as created by `scaffold`, reveal nothing and can be committed.
+fn is_plaintext(content: &str) -> bool {
+    !content.trim().is_empty() && !encryption::is_encrypted(content)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::Path;
+
+    use super::{is_input, is_plaintext};
+
+    #[test]
+    fn detects_plaintext() {
+        assert!(is_plaintext("1721\n979\n"));
+        assert!(!is_plaintext(""));
+        assert!(!is_plaintext("\n"));
+        assert!(!is_plaintext("aoc-encrypted v1\nAAAA\n"));
+    }
+
+    #[test]
+    fn detects_inputs() {
+        assert!(is_input(Path::new("data/inputs/01.txt")));
+        assert!(!is_input(Path::new("data/examples/01.txt")));
+        assert!(!is_input(Path::new("data/inputs/.keep")));
+        assert!(!is_input(Path::new("src/bin/01.rs")));
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use crate::template::commands::leaderboard::{format_elapsed, table};
+use crate::template::journal::{self, Event, Kind, Outcome};
+use crate::template::{config, Day, ANSI_BOLD, ANSI_RESET};
+
+/// Days started later than this after their unlock are measured from the first event instead, e.g. puzzles of
+/// previous years.
+const LATE_START: u64 = 24 * 60 * 60;
+
+/// How long a day took to solve.
+#[derive(Debug, PartialEq, Eq)]
+struct Summary {
+    day: Day,
+    /// Timestamp times are measured from: the unlock of the puzzle, or the first event of a late start.
+    start: u64,
+    late_start: bool,
+    /// Timestamps of the correct answers of part one and two.
+    stars: [Option<u64>; 2],
+    /// Wrong answers submitted for part one and two.
+    wrong: [usize; 2],
+}
+
+pub fn handle(day: Option<Day>) {
+    let events: Vec<Event> = journal::read()
+        .into_iter()
+        .filter(|event| day.is_none_or(|day| event.day == day))
+        .collect();
+
+    if events.is_empty() {
+        println!(
+            "The journal is empty. Scaffolding, downloading and submitting via the template adds entries to it."
+        );
+        return;
+    }
+
+    let summaries = summarize(&events, config::get().year);
+    print!("{}", render(&summaries));
+}
+
+fn summarize(events: &[Event], year: Option<u16>) -> Vec<Summary> {
+    let mut days: BTreeMap<Day, Vec<&Event>> = BTreeMap::new();
+    for event in events {
+        days.entry(event.day).or_default().push(event);
+    }
+
+    days.into_iter()
+        .map(|(day, mut events)| {
+            events.sort_by_key(|event| event.timestamp);
+            let first = events[0].timestamp;
+
+            let (start, late_start) = match year.map(|year| day.unlocks_at(year)) {
+                Some(unlock) if first < unlock + LATE_START => (unlock, false),
+                _ => (first, true),
+            };
+
+            let mut stars = [None; 2];
+            let mut wrong = [0; 2];
+
+            for event in events {
+                let Kind::Submit { part, outcome, .. } = &event.kind else {
+                    continue;
+                };
+                let Some(i) = usize::from(*part).checked_sub(1).filter(|i| *i < 2) else {
+                    continue;
+                };
+                match outcome {
+                    Outcome::Correct if stars[i].is_none() => stars[i] = Some(event.timestamp),
+                    Outcome::Incorrect if stars[i].is_none() => wrong[i] += 1,
+                    _ => {}
+                }
+            }
+
+            Summary {
+                day,
+                start,
+                late_start,
+                stars,
+                wrong,
+            }
+        })
+        .collect()
+}
+
+fn render(summaries: &[Summary]) -> String {
+    let rows: Vec<Vec<String>> = summaries
+        .iter()
+        .map(|summary| {
+            let marker = if summary.late_start { "*" } else { "" };
+            let elapsed = |star: Option<u64>| {
+                star.map_or("-".into(), |star| {
+                    format!(
+                        "{}{marker}",
+                        format_elapsed(star.saturating_sub(summary.start))
+                    )
+                })
+            };
+            let delta = match summary.stars {
+                [Some(one), Some(two)] => format_elapsed(two.saturating_sub(one)),
+                _ => "-".into(),
+            };
+
+            vec![
+                summary.day.to_string(),
+                elapsed(summary.stars[0]),
+                elapsed(summary.stars[1]),
+                delta,
+                format!("{} / {}", summary.wrong[0], summary.wrong[1]),
+            ]
+        })
+        .collect();
+
+    let mut out = format!("{ANSI_BOLD}Time from unlock to each star{ANSI_RESET}\n\n");
+    out.push_str(&table(
+        &["Day", "Part 1", "Part 2", "Part 1→2", "Wrong answers"],
+        &rows,
+        1,
+    ));
+
+    if summaries.iter().any(|summary| summary.late_start) {
+        out.push_str(
+            "\n* started more than a day after the unlock, measured from the first scaffold or download instead.\n",
+        );
+    }
+
+    out
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{render, summarize, Summary};
+    use crate::day;
+    use crate::template::journal::{Event, Kind, Outcome};
+    use crate::template::Day;
+
+    const UNLOCK: u64 = 1606798800;
+
+    fn event(timestamp: u64, day: Day, kind: Kind) -> Event {
+        Event {
+            timestamp,
+            day,
+            kind,
+        }
+    }
+
+    fn submit(part: u8, outcome: Outcome) -> Kind {
+        Kind::Submit {
+            part,
+            answer: "42".into(),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn summarizes_days() {
+        let events = vec![
+            event(UNLOCK + 10, day!(1), Kind::Scaffold),
+            event(UNLOCK + 20, day!(1), Kind::Download),
+            event(UNLOCK + 300, day!(1), submit(1, Outcome::Incorrect)),
+            event(UNLOCK + 400, day!(1), submit(1, Outcome::TooSoon)),
+            event(UNLOCK + 500, day!(1), submit(1, Outcome::Correct)),
+            event(UNLOCK + 900, day!(1), submit(2, Outcome::Correct)),
+            event(UNLOCK + 950, day!(1), submit(2, Outcome::Incorrect)),
+        ];
+
+        assert_eq!(
+            summarize(&events, Some(2020)),
+            vec![Summary {
+                day: day!(1),
+                start: UNLOCK,
+                late_start: false,
+                stars: [Some(UNLOCK + 500), Some(UNLOCK + 900)],
+                wrong: [1, 0],
+            }]
+        );
+    }
+
+    #[test]
+    fn measures_late_starts_from_first_event() {
+        let late = UNLOCK + 365 * 24 * 60 * 60;
+        let events = vec![
+            event(late + 60, day!(1), submit(1, Outcome::Correct)),
+            event(late, day!(1), Kind::Scaffold),
+        ];
+
+        let summaries = summarize(&events, Some(2020));
+        assert_eq!(summaries[0].start, late);
+        assert!(summaries[0].late_start);
+        assert_eq!(summarize(&events, None), summaries);
+
+        let rendered = render(&summaries);
+        assert!(rendered.contains("01   00:01:00*       -         -          0 / 0"));
+        assert!(rendered.contains("* started more than a day after the unlock"));
+    }
+
+    #[test]
+    fn renders_deltas() {
+        let summaries = vec![Summary {
+            day: day!(3),
+            start: UNLOCK,
+            late_start: false,
+            stars: [Some(UNLOCK + 125), Some(UNLOCK + 4000)],
+            wrong: [2, 1],
+        }];
+        assert!(render(&summaries).contains("03   00:02:05  01:06:40  01:04:35          2 / 1"));
+    }
+}
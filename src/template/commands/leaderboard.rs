@@ -0,0 +1,360 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{self, Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use tinyjson::JsonValue;
+
+use crate::template::{aoc_cli, config, Day, ANSI_BOLD, ANSI_RESET};
+
+/// Advent of Code asks to not request a leaderboard more often than every 15 minutes.
+const CACHE_DURATION: Duration = Duration::from_secs(15 * 60);
+
+const USER_AGENT: &str = "advent_of_code template (cargo leaderboard)";
+
+/// A member of a private leaderboard.
+#[derive(Debug, PartialEq)]
+struct Member {
+    name: String,
+    local_score: u64,
+    stars: u64,
+    /// Timestamps of the stars of each day, for part one and two.
+    days: BTreeMap<Day, [Option<u64>; 2]>,
+}
+
+pub fn handle(id: u64) {
+    let config = config::get();
+
+    let Some(year) = config.year else {
+        eprintln!("Leaderboards are kept per year. Set `year` in \"aoc.toml\" or pass `--year`.");
+        process::exit(1);
+    };
+
+    let cache_path = config
+        .data_dir
+        .join("leaderboards")
+        .join(format!("{year}-{id}.json"));
+
+    let json = match cached(&cache_path) {
+        Some((json, age)) if age < CACHE_DURATION => {
+            println!(
+                "Using the leaderboard fetched {} minute(s) ago.",
+                age.as_secs() / 60
+            );
+            json
+        }
+        stale => {
+            let url = format!(
+                "{}/{year}/leaderboard/private/view/{id}.json",
+                config.base_url
+            );
+            match fetch(&url).and_then(|json| parse_leaderboard(&json).map(|_| json)) {
+                Ok(json) => {
+                    if let Err(e) = store(&cache_path, &json) {
+                        eprintln!("Failed to cache the leaderboard: {e}");
+                    }
+                    json
+                }
+                Err(e) => match stale {
+                    Some((json, _)) => {
+                        eprintln!("Failed to fetch the leaderboard ({e}), using a cached copy.");
+                        json
+                    }
+                    None => {
+                        eprintln!("Failed to fetch the leaderboard: {e}");
+                        process::exit(1);
+                    }
+                },
+            }
+        }
+    };
+
+    match parse_leaderboard(&json) {
+        Ok(members) => print!("{}", render(id, year, &members)),
+        Err(e) => {
+            eprintln!("Failed to read the leaderboard: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Returns a cached leaderboard and its age.
+fn cached(path: &Path) -> Option<(String, Duration)> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or(Duration::ZERO);
+    Some((fs::read_to_string(path).ok()?, age))
+}
+
+fn store(path: &Path, json: &str) -> Result<(), std::io::Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, json)
+}
+
+/// Fetches a page with curl, authenticated with the session token used for downloads.
+fn fetch(url: &str) -> Result<String, String> {
+    let token = aoc_cli::session_token()
+        .ok_or("no session token found, see \"Configure aoc-cli integration\" in the readme")?;
+
+    // NOTE: the cookie is passed via stdin, so that it does not show up in the process list.
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail"])
+        .args(["--user-agent", USER_AGENT, "--header", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|_| "curl is not present in environment")?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(format!("Cookie: session={token}\n").as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Parses the JSON of a private leaderboard into members, ranked by their local score.
+fn parse_leaderboard(json: &str) -> Result<Vec<Member>, String> {
+    let json = JsonValue::from_str(json)
+        .map_err(|_| "unexpected response, is the session token valid?".to_string())?;
+
+    let members = json
+        .get::<HashMap<String, JsonValue>>()
+        .and_then(|root| root.get("members"))
+        .and_then(|members| members.get::<HashMap<String, JsonValue>>())
+        .ok_or("missing `members`")?;
+
+    let mut members: Vec<Member> = members
+        .iter()
+        .map(|(id, member)| parse_member(id, member).ok_or(format!("invalid member `{id}`")))
+        .collect::<Result<_, _>>()?;
+
+    members.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then(b.stars.cmp(&a.stars))
+            .then(a.name.cmp(&b.name))
+    });
+
+    Ok(members)
+}
+
+fn parse_member(id: &str, member: &JsonValue) -> Option<Member> {
+    let member = member.get::<HashMap<String, JsonValue>>()?;
+    let number = |key: &str| member.get(key)?.get::<f64>().map(|x| *x as u64);
+
+    let name = match member.get("name")?.get::<String>() {
+        Some(name) => name.clone(),
+        None => format!("(anonymous user #{id})"),
+    };
+
+    let mut days = BTreeMap::new();
+    for (day, parts) in member
+        .get("completion_day_level")?
+        .get::<HashMap<String, JsonValue>>()?
+    {
+        let parts = parts.get::<HashMap<String, JsonValue>>()?;
+        let star = |part: &str| {
+            let star = parts.get(part)?.get::<HashMap<String, JsonValue>>()?;
+            star.get("get_star_ts")?.get::<f64>().map(|x| *x as u64)
+        };
+        days.insert(day.parse().ok()?, [star("1"), star("2")]);
+    }
+
+    Some(Member {
+        name,
+        local_score: number("local_score")?,
+        stars: number("stars")?,
+        days,
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn render(id: u64, year: u16, members: &[Member]) -> String {
+    let mut out = format!("{ANSI_BOLD}Private leaderboard {id} ({year}){ANSI_RESET}\n\n");
+
+    let ranking: Vec<Vec<String>> = members
+        .iter()
+        .enumerate()
+        .map(|(i, member)| {
+            vec![
+                format!("{}.", i + 1),
+                member.name.clone(),
+                member.local_score.to_string(),
+                format!("{} ★", member.stars),
+            ]
+        })
+        .collect();
+    out.push_str(&table(&["Rank", "Name", "Score", "Stars"], &ranking, 2));
+
+    let days: Vec<Day> = members
+        .iter()
+        .flat_map(|member| member.days.keys().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    for day in days {
+        let unlock = day.unlocks_at(year);
+        let mut stars: Vec<(&Member, [Option<u64>; 2])> = members
+            .iter()
+            .filter_map(|member| Some((member, *member.days.get(&day)?)))
+            .collect();
+        // fastest to finish the day first, then everyone that only has part one.
+        stars.sort_by_key(|(_, [one, two])| (two.is_none(), two.or(*one)));
+
+        let rows: Vec<Vec<String>> = stars
+            .iter()
+            .map(|(member, [one, two])| {
+                let elapsed = |ts: &Option<u64>| {
+                    ts.map_or("-".into(), |ts| format_elapsed(ts.saturating_sub(unlock)))
+                };
+                let delta = match (one, two) {
+                    (Some(one), Some(two)) => format_elapsed(two.saturating_sub(*one)),
+                    _ => "-".into(),
+                };
+                vec![member.name.clone(), elapsed(one), elapsed(two), delta]
+            })
+            .collect();
+
+        out.push_str(&format!("\n{ANSI_BOLD}Day {day}{ANSI_RESET}\n"));
+        out.push_str(&table(&["Name", "Part 1", "Part 2", "Part 1→2"], &rows, 1));
+    }
+
+    out
+}
+
+/// Formats rows as columns. The first `left_aligned` columns are aligned left, the numbers and times after them right.
+pub(super) fn table(header: &[&str], rows: &[Vec<String>], left_aligned: usize) -> String {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |row: Vec<&str>| {
+        row.iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                let padding = " ".repeat(width - cell.chars().count());
+                if i < left_aligned {
+                    format!("{cell}{padding}")
+                } else {
+                    format!("{padding}{cell}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = format!("{}\n", format_row(header.to_vec()));
+    for row in rows {
+        out.push_str(&format_row(row.iter().map(String::as_str).collect()));
+        out.push('\n');
+    }
+    out
+}
+
+pub(super) fn format_elapsed(secs: u64) -> String {
+    let (days, secs) = (secs / 86400, secs % 86400);
+    let time = format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+
+    if days > 0 {
+        format!("{days}d {time}")
+    } else {
+        time
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_elapsed, parse_leaderboard, render};
+    use crate::day;
+
+    const LEADERBOARD: &str = r#"{
+        "event": "2020",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "alice", "stars": 3, "local_score": 10, "global_score": 0, "last_star_ts": 1606886400,
+                "completion_day_level": {
+                    "1": { "1": { "get_star_ts": 1606799100, "star_index": 1 }, "2": { "get_star_ts": 1606799700, "star_index": 2 } },
+                    "2": { "1": { "get_star_ts": 1606886400, "star_index": 3 } }
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 2, "local_score": 12, "global_score": 0, "last_star_ts": 1606800000,
+                "completion_day_level": {
+                    "1": { "1": { "get_star_ts": 1606798860, "star_index": 1 }, "2": { "get_star_ts": 1606800000, "star_index": 2 } }
+                }
+            },
+            "3": {
+                "id": 3, "name": "carol", "stars": 0, "local_score": 0, "global_score": 0, "last_star_ts": 0,
+                "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn ranks_members_by_score() {
+        let members = parse_leaderboard(LEADERBOARD).unwrap();
+        let names: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["(anonymous user #2)", "alice", "carol"]);
+        assert_eq!(
+            members[1].days[&day!(1)],
+            [Some(1606799100), Some(1606799700)]
+        );
+        assert_eq!(members[1].days[&day!(2)], [Some(1606886400), None]);
+    }
+
+    #[test]
+    fn renders_star_times_and_deltas() {
+        let members = parse_leaderboard(LEADERBOARD).unwrap();
+        let rendered = render(1, 2020, &members);
+
+        assert!(rendered.contains("1.    (anonymous user #2)     12    2 ★\n2.    alice"));
+        // alice finished day 1 fifteen minutes after the unlock, ten minutes after part one.
+        assert!(rendered
+            .contains("alice                00:05:00  00:15:00  00:10:00\n(anonymous user #2)"));
+        assert!(rendered.contains("alice  00:20:00       -         -"));
+    }
+
+    #[test]
+    fn formats_elapsed_times() {
+        assert_eq!(format_elapsed(62), "00:01:02");
+        assert_eq!(format_elapsed(90_061), "1d 01:01:01");
+    }
+
+    #[test]
+    fn rejects_invalid_responses() {
+        assert!(parse_leaderboard("<!DOCTYPE html>").is_err());
+        assert!(parse_leaderboard("{}").is_err());
+    }
+}
@@ -1,6 +1,12 @@
 pub mod all;
 pub mod download;
+pub mod inputs;
+pub mod journal;
+pub mod leaderboard;
 pub mod read;
 pub mod scaffold;
 pub mod solve;
+pub mod status;
 pub mod time;
+#[cfg(feature = "today")]
+pub mod today;
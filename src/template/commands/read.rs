@@ -1,15 +1,65 @@
-use std::process;
+use std::io::{stdout, IsTerminal, Write};
+use std::process::{self, Command, Stdio};
+use std::{env, fs};
 
-use crate::template::{aoc_cli, Day};
+use terminal_size::{terminal_size, Height, Width};
+
+use crate::template::{config, markdown, Day};
+
+/// Widest column puzzle descriptions are wrapped to, long lines are hard to read.
+const MAX_WIDTH: usize = 100;
+/// Width to wrap to if the terminal size can not be determined.
+const DEFAULT_WIDTH: usize = 80;
 
 pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+    let path = config::get().puzzle_path(day);
+
+    let Ok(puzzle) = fs::read_to_string(&path) else {
+        eprintln!(
+            "Could not read \"{}\". Try running \"cargo download {day}\" to download the puzzle.",
+            path.display()
+        );
         process::exit(1);
+    };
+
+    let size = terminal_size();
+    let width = size.map_or(DEFAULT_WIDTH, |(Width(w), _)| usize::from(w).min(MAX_WIDTH));
+    // NOTE: escape codes would end up as garbage in files and pagers that do not interpret them.
+    let is_terminal = stdout().is_terminal();
+    let rendered = markdown::render(&puzzle, width, is_terminal);
+
+    match size {
+        Some((_, Height(h))) if is_terminal && rendered.lines().count() >= h.into() => {
+            page(&rendered);
+        }
+        _ => print!("{rendered}"),
     }
+}
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
+/// Shows `text` in the pager set in `$PAGER`, or `less`. Prints it directly if no pager can be started.
+fn page(text: &str) {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less -R".into());
+    let mut parts = pager.split_whitespace();
+
+    let child = parts.next().and_then(|program| {
+        Command::new(program)
+            .args(parts)
+            // keep colors when `less` is configured through `$PAGER` without flags.
+            .env("LESS", env::var("LESS").unwrap_or_else(|_| "-R".into()))
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+
+    let Some(mut child) = child else {
+        print!("{text}");
+        return;
     };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // NOTE: the pager closes its input when the user quits early, which is not an error.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+
+    let _ = child.wait();
 }
@@ -1,10 +1,32 @@
-use std::process::{Command, Stdio};
+use std::process::{self, Command, Stdio};
 
-use crate::template::Day;
+use crate::template::{runner::RunOptions, Day};
+use crate::visualize::Output;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    alloc_stats: bool,
+    visualize: Option<Output>,
+    submit_part: Option<u8>,
+    options: &RunOptions,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
+    if dhat && alloc_stats {
+        eprintln!("The `--dhat` and `--alloc-stats` flags can not be combined.");
+        process::exit(1);
+    }
+
+    if alloc_stats {
+        cmd_args.extend(["--features".to_string(), "alloc-stats".to_string()]);
+    }
+
+    if visualize.is_some() {
+        cmd_args.extend(["--features".to_string(), "visualize".to_string()]);
+    }
+
     if dhat {
         cmd_args.extend([
             "--profile".to_string(),
@@ -23,6 +45,13 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    if let Some(output) = visualize {
+        cmd_args.push("--visualize".to_string());
+        cmd_args.push(output.to_string());
+    }
+
+    cmd_args.extend(options.to_args());
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
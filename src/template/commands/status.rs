@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use tinyjson::JsonValue;
+
+use crate::template::run_multi::child_commands::wait_with_deadline;
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::timings::Timings;
+use crate::template::{all_days, config, Day, ANSI_BOLD, ANSI_RESET};
+
+/// Time the example tests of a day may take, unless overridden with `--timeout`.
+pub const DEFAULT_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of running the example tests of a day.
+#[derive(Debug, PartialEq, Eq)]
+enum Tests {
+    Passed,
+    Failed,
+    TimedOut,
+    /// The test binary could not be built.
+    BuildFailed,
+    Skipped,
+}
+
+/// State of a data file, like the input of a day.
+#[derive(Debug, PartialEq, Eq)]
+enum DataFile {
+    Present,
+    Empty,
+    Missing,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Benchmark {
+    Stored,
+    /// The solution changed since it was benched.
+    Stale,
+    Missing,
+}
+
+struct Status {
+    day: Day,
+    scaffolded: bool,
+    input: DataFile,
+    example: DataFile,
+    tests: Tests,
+    answers: usize,
+    benchmark: Benchmark,
+}
+
+pub fn handle(run_tests: bool, timeout: Duration) {
+    let days: Vec<Day> = all_days().collect();
+    let timings = Timings::read_from_file();
+    let config = config::get();
+
+    let test_binaries = if run_tests {
+        println!("Building tests...");
+        build_tests()
+    } else {
+        HashMap::new()
+    };
+
+    let statuses: Vec<Status> = days
+        .into_iter()
+        .map(|day| {
+            let bin = PathBuf::from(get_path_for_bin(day));
+            let scaffolded = bin.exists();
+
+            let tests = if !scaffolded || !run_tests {
+                Tests::Skipped
+            } else {
+                test_binaries
+                    .get(&day)
+                    .map_or(Tests::BuildFailed, |executable| {
+                        run_tests_of(executable, timeout)
+                    })
+            };
+
+            Status {
+                day,
+                scaffolded,
+                input: data_file(&config.input_path(day)),
+                example: example_file(day),
+                tests,
+                answers: fs::read_to_string(config.puzzle_path(day))
+                    .map(|puzzle| known_answers(&puzzle).len())
+                    .unwrap_or(0),
+                benchmark: benchmark(timings.benched_at(day), modified(&bin)),
+            }
+        })
+        .collect();
+
+    print_table(&statuses);
+}
+
+fn data_file(path: &Path) -> DataFile {
+    match fs::read_to_string(path) {
+        Ok(content) if content.trim().is_empty() => DataFile::Empty,
+        Ok(_) => DataFile::Present,
+        Err(_) => DataFile::Missing,
+    }
+}
+
+/// Examples are either stored in a single file, or in one file per part (e.g. `01-2.txt`).
+fn example_file(day: Day) -> DataFile {
+    let config = config::get();
+
+    [None, Some(1), Some(2)]
+        .into_iter()
+        .map(|part| data_file(&config.example_path(day, part)))
+        .min_by_key(|state| match state {
+            DataFile::Present => 0,
+            DataFile::Empty => 1,
+            DataFile::Missing => 2,
+        })
+        .unwrap_or(DataFile::Missing)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn benchmark(benched_at: Option<SystemTime>, changed_at: Option<SystemTime>) -> Benchmark {
+    match (benched_at, changed_at) {
+        (None, _) => Benchmark::Missing,
+        (Some(benched_at), Some(changed_at)) if changed_at > benched_at => Benchmark::Stale,
+        (Some(_), _) => Benchmark::Stored,
+    }
+}
+
+/// Answers that were accepted, as recorded in the puzzle description downloaded by aoc-cli.
+fn known_answers(puzzle: &str) -> Vec<&str> {
+    puzzle
+        .match_indices("Your puzzle answer was")
+        .filter_map(|(i, _)| {
+            let rest = &puzzle[i..];
+            let start = rest.find('`')? + 1;
+            let end = start + rest[start..].find('`')?;
+            Some(&rest[start..end])
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Build the test binaries of all days at once and return their executables.
+/// Days that fail to compile are missing from the result.
+fn build_tests() -> HashMap<Day, String> {
+    // NOTE: `cargo test --no-run` stops at the first day that does not compile, `build --keep-going` does not.
+    let output = Command::new("cargo")
+        .args([
+            "build",
+            "--tests",
+            "--keep-going",
+            "--quiet",
+            "--message-format=json",
+        ])
+        .stderr(Stdio::null())
+        .output();
+
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_test_executable)
+        .collect()
+}
+
+/// Extracts the day and executable of a test binary from a cargo `compiler-artifact` JSON message.
+fn parse_test_executable(line: &str) -> Option<(Day, String)> {
+    let json = JsonValue::from_str(line).ok()?;
+    let message = json.get::<HashMap<String, JsonValue>>()?;
+
+    if message.get("reason")?.get::<String>()? != "compiler-artifact" {
+        return None;
+    }
+
+    let profile = message
+        .get("profile")?
+        .get::<HashMap<String, JsonValue>>()?;
+    if !*profile.get("test")?.get::<bool>()? {
+        return None;
+    }
+
+    let target = message.get("target")?.get::<HashMap<String, JsonValue>>()?;
+    let day = target.get("name")?.get::<String>()?.parse().ok()?;
+    let executable = message.get("executable")?.get::<String>()?;
+
+    Some((day, executable.clone()))
+}
+
+fn run_tests_of(executable: &str, timeout: Duration) -> Tests {
+    let child = Command::new(executable)
+        .arg("--quiet")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    let Ok(mut child) = child else {
+        return Tests::Failed;
+    };
+
+    match wait_with_deadline(&mut child, Some(timeout)) {
+        Ok(Some(_)) => Tests::TimedOut,
+        Ok(None) => match child.wait() {
+            Ok(status) if status.success() => Tests::Passed,
+            _ => Tests::Failed,
+        },
+        Err(_) => Tests::Failed,
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn print_table(statuses: &[Status]) {
+    let rows: Vec<[String; 7]> = statuses.iter().map(row).collect();
+    let header = [
+        "Day",
+        "Scaffolded",
+        "Input",
+        "Example",
+        "Tests",
+        "Answers",
+        "Benchmark",
+    ]
+    .map(String::from);
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .chain([&header])
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |row: &[String; 7]| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{ANSI_BOLD}{}{ANSI_RESET}", format_row(&header));
+    for row in &rows {
+        println!("{}", format_row(row));
+    }
+}
+
+fn row(status: &Status) -> [String; 7] {
+    let data_file = |state: &DataFile| match state {
+        DataFile::Present => "✓",
+        DataFile::Empty => "empty",
+        DataFile::Missing => "-",
+    };
+
+    let tests = match status.tests {
+        Tests::Passed => "✓",
+        Tests::Failed => "✖ failed",
+        Tests::TimedOut => "✖ timed out",
+        Tests::BuildFailed => "✖ build failed",
+        Tests::Skipped => "-",
+    };
+
+    let benchmark = match status.benchmark {
+        Benchmark::Stored => "✓",
+        Benchmark::Stale => "stale",
+        Benchmark::Missing => "-",
+    };
+
+    [
+        status.day.to_string(),
+        if status.scaffolded { "✓" } else { "-" }.into(),
+        data_file(&status.input).into(),
+        data_file(&status.example).into(),
+        tests.into(),
+        if status.answers == 0 {
+            "-".into()
+        } else {
+            "★".repeat(status.answers)
+        },
+        benchmark.into(),
+    ]
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{benchmark, known_answers, parse_test_executable, Benchmark};
+    use crate::day;
+
+    #[test]
+    fn parses_known_answers() {
+        let puzzle = "\\--- Day 1 ---\n\nYour puzzle answer was `514579`.\n\n\\--- Part Two ---\n\nYour puzzle answer was `241861950`.\n";
+        assert_eq!(known_answers(puzzle), vec!["514579", "241861950"]);
+        assert_eq!(known_answers("\\--- Day 1 ---\n"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn detects_stale_benchmarks() {
+        let at = |secs| Some(UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(benchmark(at(200), at(100)), Benchmark::Stored);
+        assert_eq!(benchmark(at(100), at(200)), Benchmark::Stale);
+        assert_eq!(benchmark(None, at(200)), Benchmark::Missing);
+    }
+
+    #[test]
+    fn parses_test_executables() {
+        let test = r#"{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"07"},"profile":{"test":true},"executable":"/tmp/target/debug/deps/07-abc"}"#;
+        let bin = r#"{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"07"},"profile":{"test":false},"executable":"/tmp/target/debug/07"}"#;
+        let main = r#"{"reason":"compiler-artifact","target":{"kind":["bin"],"name":"advent_of_code"},"profile":{"test":true},"executable":"/tmp/target/debug/deps/advent_of_code-abc"}"#;
+        assert_eq!(
+            parse_test_executable(test),
+            Some((day!(7), "/tmp/target/debug/deps/07-abc".into()))
+        );
+        assert_eq!(parse_test_executable(bin), None);
+        assert_eq!(parse_test_executable(main), None);
+    }
+}
@@ -1,10 +1,19 @@
 use std::collections::HashSet;
+use std::path::Path;
 
 use crate::template::run_multi::run_multi;
+use crate::template::runner::RunOptions;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days, config, readme_benchmarks, report, Day};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    report_dir: Option<&Path>,
+    with_alloc_stats: bool,
+    options: &RunOptions,
+) {
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
@@ -14,26 +23,44 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
                 all_days()
-                    .filter(|day| !stored_timings.is_day_complete(*day))
+                    .filter(|day| !stored_timings.is_day_complete(day))
                     .collect()
             }
         },
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let timings = run_multi(&days_to_run, true, true, with_alloc_stats, options).unwrap();
+
+    let mut merged_timings = stored_timings.merge(&timings);
 
     if store {
-        let merged_timings = stored_timings.merge(&timings);
+        merged_timings.push_history(timings.data.clone());
         merged_timings.store_file().unwrap();
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
-            Ok(()) => {
-                println!("Stored updated benchmarks.");
+        if !config::get().readme.benchmarks {
+            println!("Stored updated benchmarks.");
+        } else {
+            match readme_benchmarks::update(merged_timings.clone()) {
+                Ok(()) => {
+                    println!("Stored updated benchmarks.");
+                }
+                Err(_) => {
+                    eprintln!("Failed to store updated benchmarks.");
+                }
+            }
+        }
+    }
+
+    if let Some(report_dir) = report_dir {
+        println!();
+        match report::write(report_dir, &merged_timings) {
+            Ok(path) => {
+                println!("Wrote benchmark report to \"{}\".", path.display());
             }
-            Err(_) => {
-                eprintln!("Failed to store updated benchmarks.");
+            Err(e) => {
+                eprintln!("Failed to write benchmark report: {e}");
             }
         }
     }
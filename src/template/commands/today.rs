@@ -0,0 +1,207 @@
+use std::io::{stdout, Write};
+use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::template::commands::{download, read, scaffold};
+use crate::template::run_multi::get_path_for_bin;
+use crate::template::{aoc_cli, Day};
+
+/// How often the download is attempted after a puzzle unlocked, the page may not be live right away.
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Source of the current time, replaced by a fake clock in tests.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+pub fn handle(wait: bool) {
+    let clock = SystemClock;
+
+    let day = match Day::today() {
+        // a day that was scaffolded already is solved, wait for the next one.
+        Some(day) if !wait || !Path::new(&get_path_for_bin(day)).exists() => day,
+        _ if wait => wait_for_unlock(&clock),
+        _ => {
+            eprintln!(
+                "`today` command can only be run between the 1st and \
+                the 25th of december. Please use `scaffold` with a specific day, \
+                or `today --wait` to wait for the next puzzle."
+            );
+            process::exit(1)
+        }
+    };
+
+    scaffold::handle(day, false);
+
+    if wait {
+        if aoc_cli::check().is_err() {
+            // NOTE: reports the missing command and exits.
+            download::handle(day);
+        }
+        let downloaded = retry(&clock, DOWNLOAD_ATTEMPTS, DOWNLOAD_RETRY_DELAY, || {
+            aoc_cli::download(day)
+        });
+        if let Err(e) = downloaded {
+            eprintln!("failed to call aoc-cli: {e}");
+            process::exit(1);
+        }
+    } else {
+        download::handle(day);
+    }
+
+    read::handle(day);
+}
+
+/// Shows a countdown until the next puzzle unlocks and returns its day.
+pub fn wait_for_unlock(clock: &impl Clock) -> Day {
+    let (day, unlock) = Day::next_unlock(clock.now());
+    let mut stdout = stdout();
+
+    while let Ok(remaining) = (unlock - clock.now()).to_std() {
+        if remaining.is_zero() {
+            break;
+        }
+        let _ = write!(
+            stdout,
+            "\r⏳ Day {day} unlocks in {}",
+            format_countdown(remaining)
+        );
+        let _ = stdout.flush();
+        clock.sleep(remaining.min(Duration::from_secs(1)));
+    }
+
+    println!("\r🎄 Day {day} is unlocked!{}", " ".repeat(16));
+    day
+}
+
+/// Calls `f` up to `attempts` times, sleeping `delay` between failed attempts.
+fn retry<T, E: std::fmt::Display>(
+    clock: &impl Clock,
+    attempts: u32,
+    delay: Duration,
+    mut f: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut attempt = 1;
+    loop {
+        match f() {
+            Err(e) if attempt < attempts => {
+                eprintln!("Download failed ({e}), retrying in {}s...", delay.as_secs());
+                clock.sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn format_countdown(remaining: Duration) -> String {
+    // round up, so that the countdown does not show zero before the unlock.
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (days, secs) = (secs / 86400, secs % 86400);
+    let time = format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+
+    if days > 0 {
+        format!("{days}d {time}")
+    } else {
+        time
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::time::Duration;
+
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use super::{format_countdown, retry, wait_for_unlock, Clock};
+    use crate::day;
+
+    /// A clock that only advances when slept on.
+    struct FakeClock {
+        now: Cell<DateTime<Utc>>,
+        sleeps: RefCell<Vec<Duration>>,
+    }
+
+    impl FakeClock {
+        fn at(s: &str) -> Self {
+            FakeClock {
+                now: Cell::new(Utc.from_utc_datetime(&s.parse().unwrap())),
+                sleeps: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.now.set(self.now.get() + duration);
+            self.sleeps.borrow_mut().push(duration);
+        }
+    }
+
+    #[test]
+    fn waits_until_unlock() {
+        let clock = FakeClock::at("2024-12-06T04:59:57.500");
+        assert_eq!(wait_for_unlock(&clock), day!(6));
+        assert_eq!(
+            clock.now(),
+            Utc.with_ymd_and_hms(2024, 12, 6, 5, 0, 0).unwrap()
+        );
+        assert_eq!(clock.sleeps.borrow().len(), 3);
+    }
+
+    #[test]
+    fn retries_failed_attempts() {
+        let clock = FakeClock::at("2024-12-06T05:00:00");
+        let mut calls = 0;
+        let result = retry(&clock, 5, Duration::from_secs(5), || {
+            calls += 1;
+            if calls < 3 {
+                Err("not found")
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result, Ok(3));
+        assert_eq!(*clock.sleeps.borrow(), vec![Duration::from_secs(5); 2]);
+    }
+
+    #[test]
+    fn gives_up_after_last_attempt() {
+        let clock = FakeClock::at("2024-12-06T05:00:00");
+        let result: Result<(), _> = retry(&clock, 3, Duration::from_secs(1), || Err("not found"));
+        assert_eq!(result, Err("not found"));
+        assert_eq!(clock.sleeps.borrow().len(), 2);
+    }
+
+    #[test]
+    fn formats_countdown() {
+        assert_eq!(format_countdown(Duration::from_millis(59_500)), "00:01:00");
+        assert_eq!(format_countdown(Duration::from_secs(3_725)), "01:02:05");
+        assert_eq!(format_countdown(Duration::from_secs(90_000)), "1d 01:00:00");
+    }
+}
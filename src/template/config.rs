@@ -0,0 +1,434 @@
+//! Project configuration, read from `aoc.toml` in the repository root.
+//!
+//! Every setting is optional. Settings are resolved in this order, the first one that is set wins:
+//!  1. command-line flags, like `--year` or `--timeout`.
+//!  2. environment variables, like `AOC_YEAR`.
+//!  3. `aoc.toml` (or the file that `AOC_CONFIG` points to).
+//!  4. the defaults of the template.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::{env, fs, process};
+
+use toml::{Table, Value};
+
+use crate::template::Day;
+
+pub const CONFIG_FILE_PATH: &str = "aoc.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// Year of the puzzles, passed to aoc-cli.
+    pub year: Option<u16>,
+    pub inputs_dir: PathBuf,
+    pub examples_dir: PathBuf,
+    pub puzzles_dir: PathBuf,
+    /// Directory for generated files, like visualizations.
+    pub data_dir: PathBuf,
+    pub timings_file: PathBuf,
+    /// Log of scaffolds, downloads and submissions, see `cargo journal`.
+    pub journal_file: PathBuf,
+    /// File that contains the session token, passed to aoc-cli.
+    pub session_file: Option<PathBuf>,
+    /// Defaults for the `solve`, `all` and `time` commands.
+    pub run: RunConfig,
+    /// Defaults for the `time` command.
+    pub bench: BenchConfig,
+    /// Template for new solutions, instead of the built-in one.
+    pub template: Option<PathBuf>,
+    pub encryption: EncryptionConfig,
+    /// Website that leaderboards are fetched from, a local server can stand in for it.
+    pub base_url: String,
+    pub readme: ReadmeConfig,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunConfig {
+    pub timeout: Option<Duration>,
+    pub stack_size_mib: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BenchConfig {
+    pub alloc_stats: bool,
+    pub store: bool,
+    pub report_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptionConfig {
+    /// Whether downloaded inputs are encrypted, so that they can be committed.
+    pub enabled: bool,
+    /// File that contains the key, unless it is set in `AOC_INPUT_KEY`.
+    pub key_file: PathBuf,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReadmeConfig {
+    pub path: PathBuf,
+    /// Whether `time --store` updates the benchmark table in the readme.
+    pub benchmarks: bool,
+    /// Markdown heading level of the benchmark table, e.g. `##`.
+    pub heading: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::with_data_dir(PathBuf::from("data"))
+    }
+}
+
+impl Config {
+    fn with_data_dir(data_dir: PathBuf) -> Self {
+        Config {
+            year: None,
+            inputs_dir: data_dir.join("inputs"),
+            examples_dir: data_dir.join("examples"),
+            puzzles_dir: data_dir.join("puzzles"),
+            timings_file: data_dir.join("timings.json"),
+            journal_file: data_dir.join("journal.jsonl"),
+            data_dir,
+            session_file: None,
+            run: RunConfig::default(),
+            bench: BenchConfig::default(),
+            template: None,
+            encryption: EncryptionConfig {
+                enabled: false,
+                key_file: PathBuf::from(".aoc-key"),
+            },
+            base_url: "https://adventofcode.com".into(),
+            readme: ReadmeConfig {
+                path: PathBuf::from("README.md"),
+                benchmarks: true,
+                heading: "##".into(),
+            },
+        }
+    }
+
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.inputs_dir.join(format!("{day}.txt"))
+    }
+
+    /// Path of the example of a day. Examples that differ between parts are stored as e.g. `01-2.txt`.
+    pub fn example_path(&self, day: Day, part: Option<u8>) -> PathBuf {
+        match part {
+            Some(part) => self.examples_dir.join(format!("{day}-{part}.txt")),
+            None => self.examples_dir.join(format!("{day}.txt")),
+        }
+    }
+
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.puzzles_dir.join(format!("{day}.md"))
+    }
+
+    /// Directory of a data folder, as passed to `read_file` (e.g. `inputs`).
+    pub fn folder(&self, folder: &str) -> PathBuf {
+        match folder {
+            "inputs" => self.inputs_dir.clone(),
+            "examples" => self.examples_dir.clone(),
+            "puzzles" => self.puzzles_dir.clone(),
+            _ => self.data_dir.join(folder),
+        }
+    }
+
+    /// Parses a configuration file and applies overrides from the environment.
+    pub fn parse(toml: &str, env: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let table: Table = toml.parse().map_err(|e| format!("{e}"))?;
+
+        let data = section(&table, "data")?;
+        let data_dir = env("AOC_DATA_DIR")
+            .map(PathBuf::from)
+            .or(path(data, "dir")?)
+            .unwrap_or_else(|| PathBuf::from("data"));
+
+        let mut config = Config::with_data_dir(data_dir);
+
+        check_keys(
+            &table,
+            &[
+                "year",
+                "data",
+                "session",
+                "run",
+                "bench",
+                "scaffold",
+                "encryption",
+                "leaderboard",
+                "readme",
+            ],
+            "",
+        )?;
+        check_keys(
+            data,
+            &["dir", "inputs", "examples", "puzzles", "timings", "journal"],
+            "data.",
+        )?;
+
+        config.year = match env("AOC_YEAR") {
+            Some(year) => Some(
+                year.parse()
+                    .map_err(|_| format!("invalid `AOC_YEAR` `{year}`"))?,
+            ),
+            None => integer(&table, "year")?,
+        };
+
+        if let Some(inputs) = path(data, "inputs")? {
+            config.inputs_dir = inputs;
+        }
+        if let Some(examples) = path(data, "examples")? {
+            config.examples_dir = examples;
+        }
+        if let Some(puzzles) = path(data, "puzzles")? {
+            config.puzzles_dir = puzzles;
+        }
+        if let Some(timings) = path(data, "timings")? {
+            config.timings_file = timings;
+        }
+        if let Some(journal) = path(data, "journal")? {
+            config.journal_file = journal;
+        }
+
+        let session = section(&table, "session")?;
+        check_keys(session, &["file"], "session.")?;
+        config.session_file = env("AOC_SESSION_FILE")
+            .map(PathBuf::from)
+            .or(path(session, "file")?)
+            .map(|path| expand_home(&path, &env));
+
+        let run = section(&table, "run")?;
+        check_keys(run, &["timeout", "stack_size"], "run.")?;
+        config.run = RunConfig {
+            timeout: match run.get("timeout") {
+                Some(Value::Integer(secs)) if *secs > 0 => {
+                    Some(Duration::from_secs(secs.unsigned_abs()))
+                }
+                Some(Value::Float(secs)) if *secs > 0.0 => Some(Duration::from_secs_f64(*secs)),
+                Some(_) => return Err("`run.timeout` must be a positive number of seconds".into()),
+                None => None,
+            },
+            stack_size_mib: integer(run, "stack_size")?,
+        };
+
+        let bench = section(&table, "bench")?;
+        check_keys(bench, &["alloc_stats", "store", "report"], "bench.")?;
+        config.bench = BenchConfig {
+            alloc_stats: boolean(bench, "alloc_stats")?.unwrap_or(false),
+            store: boolean(bench, "store")?.unwrap_or(false),
+            report_dir: path(bench, "report")?,
+        };
+
+        let scaffold = section(&table, "scaffold")?;
+        check_keys(scaffold, &["template"], "scaffold.")?;
+        config.template = path(scaffold, "template")?;
+
+        let encryption = section(&table, "encryption")?;
+        check_keys(encryption, &["enabled", "key_file"], "encryption.")?;
+        if let Some(enabled) = boolean(encryption, "enabled")? {
+            config.encryption.enabled = enabled;
+        }
+        if let Some(key_file) = path(encryption, "key_file")? {
+            config.encryption.key_file = expand_home(&key_file, &env);
+        }
+
+        let leaderboard = section(&table, "leaderboard")?;
+        check_keys(leaderboard, &["base_url"], "leaderboard.")?;
+        if let Some(base_url) = string(leaderboard, "base_url")? {
+            config.base_url = base_url.trim_end_matches('/').to_string();
+        }
+
+        let readme = section(&table, "readme")?;
+        check_keys(readme, &["path", "benchmarks", "heading"], "readme.")?;
+        if let Some(path) = path(readme, "path")? {
+            config.readme.path = path;
+        }
+        if let Some(benchmarks) = boolean(readme, "benchmarks")? {
+            config.readme.benchmarks = benchmarks;
+        }
+        if let Some(heading) = string(readme, "heading")? {
+            config.readme.heading = heading;
+        }
+
+        Ok(config)
+    }
+}
+
+/// The configuration of the current project, read once per process.
+/// Exits the process if the configuration file is invalid.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        let path = env::var("AOC_CONFIG").unwrap_or_else(|_| CONFIG_FILE_PATH.into());
+        let toml = fs::read_to_string(&path).unwrap_or_default();
+
+        Config::parse(&toml, |name| env::var(name).ok()).unwrap_or_else(|e| {
+            eprintln!("Invalid configuration in \"{path}\": {e}.");
+            process::exit(1);
+        })
+    })
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn section<'a>(table: &'a Table, key: &str) -> Result<&'a Table, String> {
+    static EMPTY: OnceLock<Table> = OnceLock::new();
+    match table.get(key) {
+        Some(Value::Table(section)) => Ok(section),
+        Some(_) => Err(format!("`{key}` must be a table")),
+        None => Ok(EMPTY.get_or_init(Table::new)),
+    }
+}
+
+fn check_keys(table: &Table, known: &[&str], prefix: &str) -> Result<(), String> {
+    match table.keys().find(|key| !known.contains(&key.as_str())) {
+        Some(key) => Err(format!("unknown key `{prefix}{key}`")),
+        None => Ok(()),
+    }
+}
+
+fn string(table: &Table, key: &str) -> Result<Option<String>, String> {
+    match table.get(key) {
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(_) => Err(format!("`{key}` must be a string")),
+        None => Ok(None),
+    }
+}
+
+fn path(table: &Table, key: &str) -> Result<Option<PathBuf>, String> {
+    Ok(string(table, key)?.map(PathBuf::from))
+}
+
+fn boolean(table: &Table, key: &str) -> Result<Option<bool>, String> {
+    match table.get(key) {
+        Some(Value::Boolean(b)) => Ok(Some(*b)),
+        Some(_) => Err(format!("`{key}` must be a boolean")),
+        None => Ok(None),
+    }
+}
+
+fn integer<T: TryFrom<i64>>(table: &Table, key: &str) -> Result<Option<T>, String> {
+    match table.get(key) {
+        Some(Value::Integer(i)) => T::try_from(*i)
+            .map(Some)
+            .map_err(|_| format!("`{key}` is out of range")),
+        Some(_) => Err(format!("`{key}` must be an integer")),
+        None => Ok(None),
+    }
+}
+
+fn expand_home(path: &Path, env: impl Fn(&str) -> Option<String>) -> PathBuf {
+    match (path.strip_prefix("~"), env("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::Config;
+    use crate::day;
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn uses_defaults_without_file() {
+        let config = Config::parse("", no_env).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(
+            config.input_path(day!(1)),
+            PathBuf::from("data/inputs/01.txt")
+        );
+        assert_eq!(
+            config.example_path(day!(1), Some(2)),
+            PathBuf::from("data/examples/01-2.txt")
+        );
+    }
+
+    #[test]
+    fn parses_all_settings() {
+        let toml = r#"
+year = 2020
+
+[data]
+dir = "aoc"
+puzzles = "descriptions"
+
+[session]
+file = "~/.adventofcode.session"
+
+[run]
+timeout = 2.5
+stack_size = 128
+
+[bench]
+alloc_stats = true
+report = "target/report"
+
+[scaffold]
+template = "templates/day.txt"
+
+[encryption]
+enabled = true
+
+[leaderboard]
+base_url = "http://localhost:8000/"
+
+[readme]
+benchmarks = false
+heading = '###'
+"#;
+        let env = |name: &str| (name == "HOME").then(|| "/home/elf".to_string());
+        let config = Config::parse(toml, env).unwrap();
+
+        assert_eq!(config.year, Some(2020));
+        assert_eq!(config.inputs_dir, PathBuf::from("aoc/inputs"));
+        assert_eq!(config.puzzles_dir, PathBuf::from("descriptions"));
+        assert_eq!(config.timings_file, PathBuf::from("aoc/timings.json"));
+        assert_eq!(
+            config.session_file,
+            Some(PathBuf::from("/home/elf/.adventofcode.session"))
+        );
+        assert_eq!(config.run.timeout, Some(Duration::from_millis(2500)));
+        assert_eq!(config.run.stack_size_mib, Some(128));
+        assert!(config.bench.alloc_stats);
+        assert_eq!(
+            config.bench.report_dir,
+            Some(PathBuf::from("target/report"))
+        );
+        assert_eq!(config.template, Some(PathBuf::from("templates/day.txt")));
+        assert!(config.encryption.enabled);
+        assert_eq!(config.encryption.key_file, PathBuf::from(".aoc-key"));
+        assert_eq!(config.base_url, "http://localhost:8000");
+        assert!(!config.readme.benchmarks);
+        assert_eq!(config.readme.heading, "###");
+    }
+
+    #[test]
+    fn prefers_environment() {
+        let env = |name: &str| match name {
+            "AOC_YEAR" => Some("2021".to_string()),
+            "AOC_DATA_DIR" => Some("/tmp/aoc".to_string()),
+            _ => None,
+        };
+        let config = Config::parse("year = 2020", env).unwrap();
+        assert_eq!(config.year, Some(2021));
+        assert_eq!(config.examples_dir, PathBuf::from("/tmp/aoc/examples"));
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(Config::parse("yaer = 2020", no_env).is_err());
+        assert!(Config::parse("year = \"2020\"", no_env).is_err());
+        assert!(Config::parse("[run]\ntimeout = -1", no_env).is_err());
+        assert!(Config::parse("[bench]\nstore = 1", no_env).is_err());
+        assert!(Config::parse("data = 1", no_env).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 #[cfg(feature = "today")]
-use chrono::{Datelike, FixedOffset, Utc};
+use chrono::{DateTime, Datelike, Days, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
 
 #[cfg(feature = "today")]
 const SERVER_UTC_OFFSET: i32 = -5;
@@ -41,20 +41,69 @@ impl Day {
     pub fn into_inner(self) -> u8 {
         self.0
     }
+
+    /// Returns the unix timestamp at which the puzzle of this day unlocks in `year`, midnight at UTC-5.
+    pub fn unlocks_at(self, year: u16) -> u64 {
+        // days between the unix epoch and december `self`, see http://howardhinnant.github.io/date_algorithms.html
+        let year = u64::from(year);
+        let (era, year_of_era) = (year / 400, year % 400);
+        let day_of_year = 275 + u64::from(self.0) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        days * 86400 + 5 * 3600
+    }
 }
 
 #[cfg(feature = "today")]
 impl Day {
     /// Returns the current day if it's between the 1st and the 25th of december, `None` otherwise.
     pub fn today() -> Option<Self> {
-        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
-        let today = Utc::now().with_timezone(&offset);
+        Self::today_at(Utc::now())
+    }
+
+    /// Returns the day of advent at `now` in the timezone of the Advent of Code server, if any.
+    pub fn today_at(now: DateTime<Utc>) -> Option<Self> {
+        let today = now.with_timezone(&server_offset());
         if today.month() == 12 && today.day() <= 25 {
             Self::new(u8::try_from(today.day()).ok()?)
         } else {
             None
         }
     }
+
+    /// Returns the next day to unlock after `now`, and the moment it unlocks.
+    /// Puzzles unlock at midnight in the timezone of the Advent of Code server.
+    pub fn next_unlock(now: DateTime<Utc>) -> (Self, DateTime<Utc>) {
+        let offset = server_offset();
+        let tomorrow = now.with_timezone(&offset).date_naive() + Days::new(1);
+
+        let date = if tomorrow.month() == 12 && tomorrow.day() <= 25 {
+            tomorrow
+        } else {
+            // the next advent starts this year, unless this year's advent is over.
+            let year = if tomorrow.month() == 12 {
+                tomorrow.year() + 1
+            } else {
+                tomorrow.year()
+            };
+            NaiveDate::from_ymd_opt(year, 12, 1).expect("december 1st is a valid date")
+        };
+
+        let unlock = offset
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .single()
+            .expect("fixed offsets are unambiguous")
+            .with_timezone(&Utc);
+
+        // NOTE: `date` is between the 1st and the 25th of december.
+        (Self(u8::try_from(date.day()).unwrap_or(1)), unlock)
+    }
+}
+
+#[cfg(feature = "today")]
+fn server_offset() -> FixedOffset {
+    FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600).expect("the server offset is in range")
 }
 
 impl Display for Day {
@@ -187,6 +236,59 @@ mod tests {
         assert_eq!(iter.next(), Some(Day(25)));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn unlock_timestamps() {
+        assert_eq!(Day(1).unlocks_at(2020), 1606798800);
+        assert_eq!(Day(25).unlocks_at(2024), 1735102800);
+    }
+
+    #[cfg(feature = "today")]
+    mod today {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        use super::Day;
+
+        fn utc(s: &str) -> DateTime<Utc> {
+            Utc.from_utc_datetime(&s.parse().unwrap())
+        }
+
+        #[test]
+        fn today_uses_server_timezone() {
+            assert_eq!(Day::today_at(utc("2024-12-01T04:59:59")), None);
+            assert_eq!(Day::today_at(utc("2024-12-01T05:00:00")), Some(Day(1)));
+            assert_eq!(Day::today_at(utc("2024-12-26T04:59:59")), Some(Day(25)));
+            assert_eq!(Day::today_at(utc("2024-12-26T05:00:00")), None);
+        }
+
+        #[test]
+        fn next_unlock_during_advent() {
+            assert_eq!(
+                Day::next_unlock(utc("2024-12-04T04:59:00")),
+                (Day(4), utc("2024-12-04T05:00:00"))
+            );
+            assert_eq!(
+                Day::next_unlock(utc("2024-12-04T05:00:00")),
+                (Day(5), utc("2024-12-05T05:00:00"))
+            );
+        }
+
+        #[test]
+        fn next_unlock_outside_advent() {
+            assert_eq!(
+                Day::next_unlock(utc("2024-11-30T12:00:00")),
+                (Day(1), utc("2024-12-01T05:00:00"))
+            );
+            assert_eq!(
+                Day::next_unlock(utc("2024-12-25T05:00:00")),
+                (Day(1), utc("2025-12-01T05:00:00"))
+            );
+            assert_eq!(
+                Day::next_unlock(utc("2024-01-10T00:00:00")),
+                (Day(1), utc("2024-12-01T05:00:00"))
+            );
+        }
+    }
 }
 
 /* -------------------------------------------------------------------------- */
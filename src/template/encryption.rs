@@ -0,0 +1,197 @@
+//! Encryption of puzzle inputs, so that they can be committed without publishing them.
+//!
+//! Encrypted files start with a header line, followed by the base64 encoded nonce and ciphertext. The nonce is derived
+//! from the key and the plaintext, so encrypting the same input twice yields the same file and does not show up as a
+//! change in git.
+
+use std::fs;
+use std::path::Path;
+use std::{env, fmt::Write};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
+
+use crate::template::config;
+
+const HEADER: &str = "aoc-encrypted v1";
+const NONCE_LENGTH: usize = 24;
+const LINE_LENGTH: usize = 76;
+
+/// Environment variable that holds the key, as an alternative to the key file.
+pub const KEY_VARIABLE: &str = "AOC_INPUT_KEY";
+
+/// A 256 bit key, stored as 64 hex digits.
+pub struct Key([u8; 32]);
+
+impl Key {
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let hex = hex.trim();
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err("expected a key of 64 hex digits".into());
+        }
+
+        let mut key = [0; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+                .map_err(|_| "expected a key of 64 hex digits")?;
+        }
+        Ok(Key(key))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+    }
+
+    /// Generates a random key from the randomness of the operating system.
+    pub fn generate() -> Result<Self, String> {
+        let mut key = [0; 32];
+        OsRng.try_fill_bytes(&mut key).map_err(|e| e.to_string())?;
+        Ok(Key(key))
+    }
+
+    /// Reads the key from `AOC_INPUT_KEY`, or from the configured key file.
+    pub fn load() -> Result<Self, String> {
+        if let Ok(hex) = env::var(KEY_VARIABLE) {
+            return Key::from_hex(&hex).map_err(|e| format!("invalid `{KEY_VARIABLE}`: {e}"));
+        }
+
+        let path = &config::get().encryption.key_file;
+        let hex = fs::read_to_string(path).map_err(|_| {
+            format!(
+                "no key found, set `{KEY_VARIABLE}` or store the key in \"{}\"",
+                path.display()
+            )
+        })?;
+        Key::from_hex(&hex).map_err(|e| format!("invalid key in \"{}\": {e}", path.display()))
+    }
+}
+
+pub fn is_encrypted(content: &str) -> bool {
+    content.starts_with(HEADER)
+}
+
+pub fn encrypt(plaintext: &str, key: &Key) -> String {
+    let digest = Sha256::new()
+        .chain_update(key.0)
+        .chain_update(plaintext)
+        .finalize();
+    let nonce = XNonce::from_slice(&digest[..NONCE_LENGTH]);
+
+    let ciphertext = XChaCha20Poly1305::new(&key.0.into())
+        .encrypt(nonce, plaintext.as_bytes())
+        .expect("encrypting into memory can not fail");
+
+    let encoded = STANDARD.encode([nonce.as_slice(), &ciphertext].concat());
+
+    let mut content = format!("{HEADER}\n");
+    for line in encoded.as_bytes().chunks(LINE_LENGTH) {
+        content.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+        content.push('\n');
+    }
+    content
+}
+
+pub fn decrypt(content: &str, key: &Key) -> Result<String, String> {
+    let encoded: String = content
+        .strip_prefix(HEADER)
+        .ok_or("not an encrypted file")?
+        .split_whitespace()
+        .collect();
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|_| "the encrypted file is damaged")?;
+
+    if bytes.len() < NONCE_LENGTH {
+        return Err("the encrypted file is damaged".into());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LENGTH);
+
+    let plaintext = XChaCha20Poly1305::new(&key.0.into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "the file was encrypted with a different key, or is damaged")?;
+
+    String::from_utf8(plaintext).map_err(|_| "the decrypted file is not valid UTF-8".into())
+}
+
+/// Decrypts `content` if it is encrypted, and returns it unchanged otherwise.
+pub fn decrypt_if_encrypted(content: String) -> Result<String, String> {
+    if is_encrypted(&content) {
+        decrypt(&content, &Key::load()?)
+    } else {
+        Ok(content)
+    }
+}
+
+/// Encrypts a plaintext file in place. Returns whether the file was encrypted, empty files are left alone.
+pub fn encrypt_file(path: &Path, key: &Key) -> Result<bool, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if content.trim().is_empty() || is_encrypted(&content) {
+        return Ok(false);
+    }
+    fs::write(path, encrypt(&content, key)).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{decrypt, encrypt, is_encrypted, Key};
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn round_trips_inputs() {
+        let key = Key::from_hex(KEY).unwrap();
+        let input = "1721\n979\n366\n299\n675\n1456\n".repeat(40);
+
+        let encrypted = encrypt(&input, &key);
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("1721"));
+        assert!(encrypted.lines().all(|line| line.len() <= 76));
+        assert_eq!(decrypt(&encrypted, &key).unwrap(), input);
+    }
+
+    #[test]
+    fn encrypts_deterministically() {
+        let key = Key::from_hex(KEY).unwrap();
+        assert_eq!(encrypt("input", &key), encrypt("input", &key));
+        assert_ne!(encrypt("input", &key), encrypt("other input", &key));
+    }
+
+    #[test]
+    fn rejects_wrong_keys_and_damaged_files() {
+        let key = Key::from_hex(KEY).unwrap();
+        let other = Key::from_hex(&KEY.replace("00", "ff")).unwrap();
+        let encrypted = encrypt("input", &key);
+
+        assert!(decrypt(&encrypted, &other).is_err());
+        let mut damaged = encrypted.clone();
+        let byte = if &encrypted[20..21] == "x" { "y" } else { "x" };
+        damaged.replace_range(20..21, byte);
+        assert!(decrypt(&damaged, &key).is_err());
+        assert!(decrypt("aoc-encrypted v1\n", &key).is_err());
+        assert!(decrypt("plaintext", &key).is_err());
+    }
+
+    #[test]
+    fn generates_random_keys() {
+        let key = Key::generate().unwrap();
+        assert_eq!(Key::from_hex(&key.to_hex()).unwrap().to_hex(), key.to_hex());
+        assert_ne!(key.to_hex(), Key::generate().unwrap().to_hex());
+    }
+
+    #[test]
+    fn parses_hex_keys() {
+        assert_eq!(Key::from_hex(&format!("{KEY}\n")).unwrap().to_hex(), KEY);
+        assert!(Key::from_hex("abc").is_err());
+        assert!(Key::from_hex(&KEY.replace('0', "g")).is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Log of what happened when while solving a puzzle, used to measure how long each puzzle took.
+//!
+//! Events are appended to a JSON lines file, one event per line, so that they never have to be rewritten.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tinyjson::JsonValue;
+
+use crate::template::{config, Day};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Scaffold,
+    Download,
+    Submit {
+        part: u8,
+        answer: String,
+        outcome: Outcome,
+    },
+}
+
+/// How the website responded to a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    /// The answer was submitted too soon after a wrong one and was not checked.
+    TooSoon,
+    /// The part was solved already, or the response was not recognized.
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// Unix timestamp, in seconds.
+    pub timestamp: u64,
+    pub day: Day,
+    pub kind: Kind,
+}
+
+impl Outcome {
+    /// Classifies the response of the website, as printed by `aoc submit`.
+    pub fn from_response(response: &str) -> Self {
+        if response.contains("That's the right answer") {
+            Outcome::Correct
+        } else if response.contains("That's not the right answer") {
+            Outcome::Incorrect
+        } else if response.contains("You gave an answer too recently") {
+            Outcome::TooSoon
+        } else {
+            Outcome::Unknown
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::Incorrect => "incorrect",
+            Outcome::TooSoon => "too_soon",
+            Outcome::Unknown => "unknown",
+        }
+    }
+}
+
+impl Event {
+    pub fn now(day: Day, kind: Kind) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Event {
+            timestamp,
+            day,
+            kind,
+        }
+    }
+}
+
+/// Appends an event to the journal. Failures are reported, but do not interrupt the command that caused the event.
+pub fn record(event: &Event) {
+    let path = &config::get().journal_file;
+
+    let result = (|| -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let json = JsonValue::from(event)
+            .stringify()
+            .map_err(io::Error::other)?;
+        writeln!(file, "{json}")
+    })();
+
+    if let Err(e) = result {
+        eprintln!("Failed to write to the journal \"{}\": {e}", path.display());
+    }
+}
+
+/// Reads all events of the journal. Lines that can not be parsed are skipped.
+pub fn read() -> Vec<Event> {
+    fs::read_to_string(&config::get().journal_file)
+        .map(|journal| parse(&journal))
+        .unwrap_or_default()
+}
+
+fn parse(journal: &str) -> Vec<Event> {
+    journal
+        .lines()
+        .filter_map(|line| Event::try_from(&JsonValue::from_str(line).ok()?).ok())
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl From<&Event> for JsonValue {
+    fn from(event: &Event) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(event.timestamp as f64),
+        );
+        map.insert("day".into(), JsonValue::String(event.day.to_string()));
+
+        let kind = match &event.kind {
+            Kind::Scaffold => "scaffold",
+            Kind::Download => "download",
+            Kind::Submit {
+                part,
+                answer,
+                outcome,
+            } => {
+                map.insert("part".into(), JsonValue::Number(f64::from(*part)));
+                map.insert("answer".into(), JsonValue::String(answer.clone()));
+                map.insert("outcome".into(), JsonValue::String(outcome.as_str().into()));
+                "submit"
+            }
+        };
+        map.insert("kind".into(), JsonValue::String(kind.into()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Event {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected event to be a JSON object.")?;
+
+        let string = |key: &str| json.get(key).and_then(|v| v.get::<String>());
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected event.timestamp to be a number.")? as u64;
+
+        let day = string("day")
+            .and_then(|day| Day::from_str(day).ok())
+            .ok_or("Expected event.day to be a Day struct.")?;
+
+        let kind = match string("kind").map(String::as_str) {
+            Some("scaffold") => Kind::Scaffold,
+            Some("download") => Kind::Download,
+            Some("submit") => {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let part = json
+                    .get("part")
+                    .and_then(|v| v.get::<f64>().copied())
+                    .ok_or("Expected event.part to be a number.")? as u8;
+                let outcome = match string("outcome").map(String::as_str) {
+                    Some("correct") => Outcome::Correct,
+                    Some("incorrect") => Outcome::Incorrect,
+                    Some("too_soon") => Outcome::TooSoon,
+                    _ => Outcome::Unknown,
+                };
+                Kind::Submit {
+                    part,
+                    answer: string("answer").cloned().unwrap_or_default(),
+                    outcome,
+                }
+            }
+            _ => return Err("Expected event.kind to be a known kind.".into()),
+        };
+
+        Ok(Event {
+            timestamp,
+            day,
+            kind,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use tinyjson::JsonValue;
+
+    use super::{parse, Event, Kind, Outcome};
+    use crate::day;
+
+    #[test]
+    fn classifies_responses() {
+        let response = |s: &str| Outcome::from_response(s);
+        assert_eq!(
+            response("That's the right answer! You are one gold star closer."),
+            Outcome::Correct
+        );
+        assert_eq!(
+            response("That's not the right answer; your answer is too low."),
+            Outcome::Incorrect
+        );
+        assert_eq!(
+            response("You gave an answer too recently; you have to wait after submitting an answer before trying again."),
+            Outcome::TooSoon
+        );
+        assert_eq!(
+            response("You don't seem to be solving the right level."),
+            Outcome::Unknown
+        );
+    }
+
+    #[test]
+    fn round_trips_events() {
+        let events = vec![
+            Event {
+                timestamp: 1606798805,
+                day: day!(1),
+                kind: Kind::Scaffold,
+            },
+            Event {
+                timestamp: 1606799100,
+                day: day!(1),
+                kind: Kind::Submit {
+                    part: 2,
+                    answer: "241861950".into(),
+                    outcome: Outcome::Incorrect,
+                },
+            },
+        ];
+
+        let journal: String = events
+            .iter()
+            .map(|e| JsonValue::from(e).stringify().unwrap() + "\n")
+            .collect();
+
+        assert_eq!(parse(&journal), events);
+    }
+
+    #[test]
+    fn skips_invalid_lines() {
+        let journal = "{\"timestamp\":1,\"day\":\"01\",\"kind\":\"download\"}\nnot json\n{\"kind\":\"scaffold\"}\n";
+        assert_eq!(
+            parse(journal),
+            vec![Event {
+                timestamp: 1,
+                day: day!(1),
+                kind: Kind::Download
+            }]
+        );
+    }
+}
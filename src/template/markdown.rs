@@ -0,0 +1,542 @@
+/// Renders the markdown that aoc-cli stores for puzzle descriptions to ANSI-styled terminal output.
+/// Supports the subset of markdown that puzzle descriptions use: headings, paragraphs, lists, code blocks,
+/// horizontal rules, emphasis, inline code and links.
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_CYAN: &str = "\x1b[36m";
+const ANSI_YELLOW: &str = "\x1b[93m";
+const ANSI_UNDERLINE: &str = "\x1b[4m";
+
+/// Indentation of code blocks.
+const CODE_INDENT: &str = "    ";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Style {
+    strong: bool,
+    emphasis: bool,
+    code: bool,
+    link: bool,
+    heading: bool,
+}
+
+impl Style {
+    fn ansi(self) -> String {
+        let mut codes = String::new();
+        if self.heading {
+            codes.push_str(ANSI_BOLD);
+            codes.push_str(ANSI_GREEN);
+        }
+        if self.code {
+            codes.push_str(ANSI_CYAN);
+        }
+        if self.emphasis {
+            // puzzles use emphasis to highlight important bits, like the glowing text on the website.
+            codes.push_str(ANSI_BOLD);
+            codes.push_str(ANSI_YELLOW);
+        }
+        if self.strong {
+            codes.push_str(ANSI_BOLD);
+        }
+        if self.link {
+            codes.push_str(ANSI_UNDERLINE);
+        }
+        codes
+    }
+}
+
+/// A piece of text that is rendered in a single style.
+type Span = (String, Style);
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Debug, PartialEq, Eq)]
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    ListItem(String, String),
+    Code(Vec<String>),
+    Rule,
+}
+
+/// Renders `markdown`, wrapping text to `width` columns. Without `styled`, the text is rendered without any ANSI
+/// codes, e.g. to write it to a file.
+pub fn render(markdown: &str, width: usize, styled: bool) -> String {
+    let mut out = String::new();
+
+    let mut previous: Option<&Block> = None;
+
+    for block in &parse_blocks(markdown) {
+        // NOTE: items of a list are kept together, every other block is separated by an empty line.
+        let continues_list = matches!(
+            (previous, block),
+            (Some(Block::ListItem(..)), Block::ListItem(..))
+        );
+        if previous.is_some() && !continues_list {
+            out.push('\n');
+        }
+        previous = Some(block);
+
+        match block {
+            Block::Heading(text) => {
+                let base = Style {
+                    heading: true,
+                    ..Style::default()
+                };
+                push_wrapped(&mut out, &parse_inline(text, base), width, styled, "", "");
+            }
+            Block::Paragraph(text) => {
+                push_wrapped(
+                    &mut out,
+                    &parse_inline(text, Style::default()),
+                    width,
+                    styled,
+                    "",
+                    "",
+                );
+            }
+            Block::ListItem(marker, text) => {
+                let indent = " ".repeat(marker.chars().count());
+                let spans = parse_inline(text, Style::default());
+                push_wrapped(&mut out, &spans, width, styled, marker, &indent);
+            }
+            Block::Code(lines) => {
+                // code blocks are not wrapped, their whitespace is significant.
+                let style = Style {
+                    code: true,
+                    ..Style::default()
+                };
+                for line in lines {
+                    out.push_str(CODE_INDENT);
+                    push_styled(&mut out, line, style, styled);
+                    out.push('\n');
+                }
+            }
+            Block::Rule => {
+                out.push_str(&"─".repeat(width.min(40)));
+                out.push('\n');
+            }
+        }
+    }
+
+    out
+}
+
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut blocks = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut i = 0;
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+    };
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+            i += 1;
+            continue;
+        }
+
+        if trimmed.starts_with("```") {
+            flush(&mut paragraph, &mut blocks);
+            let code: Vec<String> = lines[i + 1..]
+                .iter()
+                .take_while(|l| !l.trim().starts_with("```"))
+                .map(|l| (*l).to_string())
+                .collect();
+            i += code.len() + 2;
+            blocks.push(Block::Code(code));
+            continue;
+        }
+
+        if paragraph.is_empty() && (line.starts_with(CODE_INDENT) || line.starts_with('\t')) {
+            let code: Vec<String> = lines[i..]
+                .iter()
+                .take_while(|l| l.starts_with(CODE_INDENT) || l.starts_with('\t'))
+                .map(|l| {
+                    l.strip_prefix(CODE_INDENT)
+                        .or_else(|| l.strip_prefix('\t'))
+                        .unwrap_or(l)
+                        .to_string()
+                })
+                .collect();
+            i += code.len();
+            blocks.push(Block::Code(code));
+            continue;
+        }
+
+        if let Some(text) = atx_heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(text.to_string()));
+            i += 1;
+            continue;
+        }
+
+        // setext headings are underlined by a line of `=` or `-`.
+        if paragraph.is_empty() && lines.get(i + 1).is_some_and(|next| is_underline(next)) {
+            blocks.push(Block::Heading(trimmed.to_string()));
+            i += 2;
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+            i += 1;
+            continue;
+        }
+
+        if let Some((marker, text)) = list_item(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            let mut item = vec![text];
+            i += 1;
+            // continuation lines of an item are indented.
+            while let Some(next) = lines.get(i) {
+                if next.trim().is_empty()
+                    || !next.starts_with(' ')
+                    || list_item(next.trim()).is_some()
+                {
+                    break;
+                }
+                item.push(next.trim());
+                i += 1;
+            }
+            blocks.push(Block::ListItem(marker, item.join(" ")));
+            continue;
+        }
+
+        paragraph.push(trimmed);
+        i += 1;
+    }
+
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn atx_heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    if (1..=6).contains(&level) && (text.is_empty() || text.starts_with(' ')) {
+        Some(text.trim().trim_end_matches('#').trim_end())
+    } else {
+        None
+    }
+}
+
+fn is_underline(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 2 && (line.chars().all(|c| c == '=') || line.chars().all(|c| c == '-'))
+}
+
+fn is_rule(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.len() >= 3 && ['*', '-', '_'].iter().any(|r| chars.iter().all(|c| c == r))
+}
+
+/// Splits a list item into the marker to render and its text.
+fn list_item(line: &str) -> Option<(String, &str)> {
+    let (marker, text) = line.split_once(' ')?;
+
+    let marker = if ["*", "-", "+"].contains(&marker) {
+        "  • ".to_string()
+    } else if marker.ends_with('.')
+        && marker[..marker.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_digit())
+    {
+        format!("  {marker} ")
+    } else {
+        return None;
+    };
+
+    Some((marker, text.trim_start()))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Splits inline markdown into styled spans.
+fn parse_inline(text: &str, base: Style) -> Vec<Span> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans: Vec<Span> = vec![];
+    let mut style = base;
+    let mut current = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        let next_style = match c {
+            '\\' if next.is_some_and(|n| n.is_ascii_punctuation()) => {
+                current.push(next.unwrap());
+                i += 2;
+                continue;
+            }
+            '`' => Style {
+                code: !style.code,
+                ..style
+            },
+            '*' | '_' if next == Some(c) => {
+                i += 1;
+                Style {
+                    strong: !style.strong,
+                    ..style
+                }
+            }
+            // NOTE: code is taken literally, unless the whole span is emphasized like in `*42*`.
+            '*' | '_' if style.code && !is_code_edge(&chars, i) => {
+                current.push(c);
+                i += 1;
+                continue;
+            }
+            // NOTE: underscores inside of words, like in `snake_case`, are not emphasis.
+            '*' | '_' if c == '*' || is_boundary(&chars, i) => Style {
+                emphasis: !style.emphasis,
+                ..style
+            },
+            '[' if !style.code && link(&chars, i).is_some() => {
+                let (label, end) = link(&chars, i).unwrap();
+                push_span(&mut spans, &mut current, style);
+                let link_style = Style {
+                    link: true,
+                    ..style
+                };
+                spans.extend(parse_inline(&label, link_style));
+                i = end;
+                continue;
+            }
+            _ => {
+                current.push(c);
+                i += 1;
+                continue;
+            }
+        };
+
+        push_span(&mut spans, &mut current, style);
+        style = next_style;
+        i += 1;
+    }
+
+    push_span(&mut spans, &mut current, style);
+    spans
+}
+
+fn push_span(spans: &mut Vec<Span>, current: &mut String, style: Style) {
+    if !current.is_empty() {
+        spans.push((std::mem::take(current), style));
+    }
+}
+
+fn is_code_edge(chars: &[char], i: usize) -> bool {
+    i.checked_sub(1).and_then(|i| chars.get(i)) == Some(&'`') || chars.get(i + 1) == Some(&'`')
+}
+
+fn is_boundary(chars: &[char], i: usize) -> bool {
+    let before = i.checked_sub(1).and_then(|i| chars.get(i));
+    let after = chars.get(i + 1);
+    !(before.is_some_and(|c| c.is_alphanumeric()) && after.is_some_and(|c| c.is_alphanumeric()))
+}
+
+/// Parses a link `[label](url)` starting at `start`, returning its label and the index after it.
+fn link(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let close = start + chars[start..].iter().position(|c| *c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + chars[close..].iter().position(|c| *c == ')')?;
+    Some((chars[start + 1..close].iter().collect(), end + 1))
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Appends `spans` to `out`, breaking lines between words so that they fit into `width` columns.
+/// The first line starts with `first_prefix`, the following ones with `prefix`.
+fn push_wrapped(
+    out: &mut String,
+    spans: &[Span],
+    width: usize,
+    styled: bool,
+    first_prefix: &str,
+    prefix: &str,
+) {
+    // a word is made up of all spans between two whitespace characters.
+    let mut words: Vec<Vec<Span>> = vec![vec![]];
+    for (text, style) in spans {
+        for (i, part) in text.split(' ').enumerate() {
+            if i > 0 && !words.last().unwrap().is_empty() {
+                words.push(vec![]);
+            }
+            if !part.is_empty() {
+                words.last_mut().unwrap().push((part.to_string(), *style));
+            }
+        }
+    }
+
+    let mut line_width = first_prefix.chars().count();
+    let mut line_start = true;
+    out.push_str(first_prefix);
+
+    for word in words.iter().filter(|w| !w.is_empty()) {
+        let word_width: usize = word.iter().map(|(text, _)| text.chars().count()).sum();
+
+        if !line_start && line_width + 1 + word_width > width {
+            out.push('\n');
+            out.push_str(prefix);
+            line_width = prefix.chars().count();
+            line_start = true;
+        }
+
+        if !line_start {
+            out.push(' ');
+            line_width += 1;
+        }
+
+        for (text, style) in word {
+            push_styled(out, text, *style, styled);
+        }
+
+        line_width += word_width;
+        line_start = false;
+    }
+
+    out.push('\n');
+}
+
+/// Appends `text` in `style`, or as it is if it is not `styled`.
+fn push_styled(out: &mut String, text: &str, style: Style, styled: bool) {
+    if !styled || style == Style::default() {
+        out.push_str(text);
+    } else {
+        out.push_str(&style.ansi());
+        out.push_str(text);
+        out.push_str(ANSI_RESET);
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_blocks, parse_inline, render, Block, Style};
+
+    fn strip_ansi(text: &str) -> String {
+        let mut plain = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| *c == 'm');
+            } else {
+                plain.push(c);
+            }
+        }
+        plain
+    }
+
+    const PUZZLE: &str = r"\--- Day 1: Report Repair ---
+----------
+
+After saving Christmas *five years in a row*, you've decided to take a [vacation](https://en.wikipedia.org/wiki/Vacation).
+
+For example, suppose your expense report contained the following:
+
+```
+1721
+979
+```
+
+In this list, the two entries that sum to `2020` are `1721` and `299`. Multiplying them together produces `1721 * 299 = 514579`, so the answer is `*514579*`.
+
+*   Sum the entries.
+*   Multiply them.
+";
+
+    #[test]
+    fn parses_blocks() {
+        let blocks = parse_blocks(PUZZLE);
+        assert_eq!(
+            blocks[0],
+            Block::Heading(r"\--- Day 1: Report Repair ---".into())
+        );
+        assert_eq!(blocks[3], Block::Code(vec!["1721".into(), "979".into()]));
+        assert_eq!(
+            blocks[5],
+            Block::ListItem("  • ".into(), "Sum the entries.".into())
+        );
+        assert_eq!(blocks.len(), 7);
+    }
+
+    #[test]
+    fn parses_inline_styles() {
+        let spans = parse_inline(r"a *b* `*d*` \*e\* [f](g) snake_case", Style::default());
+        let emphasis = Style {
+            emphasis: true,
+            ..Style::default()
+        };
+        let code = Style {
+            code: true,
+            ..Style::default()
+        };
+        assert_eq!(
+            spans,
+            vec![
+                ("a ".into(), Style::default()),
+                ("b".into(), emphasis),
+                (" ".into(), Style::default()),
+                (
+                    "d".into(),
+                    Style {
+                        emphasis: true,
+                        ..code
+                    }
+                ),
+                (" *e* ".into(), Style::default()),
+                (
+                    "f".into(),
+                    Style {
+                        link: true,
+                        ..Style::default()
+                    }
+                ),
+                (" snake_case".into(), Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_plain_text() {
+        let rendered = strip_ansi(&render(PUZZLE, 200, true));
+        assert!(rendered
+            .starts_with("--- Day 1: Report Repair ---\n\nAfter saving Christmas five years"));
+        assert!(rendered.contains("\n    1721\n    979\n"));
+        assert!(rendered.contains("produces 1721 * 299 = 514579, so the answer is 514579."));
+        assert!(rendered.ends_with("  • Sum the entries.\n  • Multiply them.\n"));
+    }
+
+    #[test]
+    fn renders_without_styles() {
+        let plain = render(PUZZLE, 200, false);
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(plain, strip_ansi(&render(PUZZLE, 200, true)));
+    }
+
+    #[test]
+    fn wraps_to_width() {
+        let rendered = strip_ansi(&render(PUZZLE, 30, true));
+        assert!(rendered.lines().all(|l| l.chars().count() <= 30));
+        assert!(rendered.contains("After saving Christmas five\nyears in a row, you've decided\n"));
+    }
+
+    #[test]
+    fn indents_wrapped_list_items() {
+        let rendered = strip_ansi(&render("* one two three four", 12, true));
+        assert_eq!(rendered, "  • one two\n    three\n    four\n");
+    }
+}
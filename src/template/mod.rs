@@ -1,16 +1,25 @@
-use std::{env, fs};
+use std::fs;
 
+pub mod alloc_stats;
 pub mod aoc_cli;
 pub mod commands;
+pub mod config;
 pub mod runner;
 
 pub use day::*;
 
 mod day;
+mod encryption;
+mod journal;
+mod markdown;
 mod readme_benchmarks;
+mod report;
 mod run_multi;
 mod timings;
 
+#[cfg(all(feature = "dhat-heap", feature = "alloc-stats"))]
+compile_error!("the `dhat-heap` and `alloc-stats` features can not be enabled at the same time.");
+
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
@@ -18,22 +27,21 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    let filepath = config::get().folder(folder).join(format!("{day}.txt"));
+    let f = fs::read_to_string(filepath).expect("could not open input file");
+    encryption::decrypt_if_encrypted(f)
+        .unwrap_or_else(|e| panic!("could not decrypt input file: {e}"))
 }
 
 /// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join(folder)
+    let filepath = config::get()
+        .folder(folder)
         .join(format!("{day}-{part}.txt"));
-    let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    let f = fs::read_to_string(filepath).expect("could not open input file");
+    encryption::decrypt_if_encrypted(f)
+        .unwrap_or_else(|e| panic!("could not decrypt input file: {e}"))
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
@@ -59,10 +67,16 @@ macro_rules! solution {
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[cfg(feature = "alloc-stats")]
+        #[global_allocator]
+        static ALLOC: $crate::template::alloc_stats::CountingAlloc =
+            $crate::template::alloc_stats::CountingAlloc;
+
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            // NOTE: parts run on their own threads, which may outlive a timed out run.
+            let input: &'static str = $crate::template::read_file("inputs", DAY).leak();
+            $( run_part($func, input, DAY, $part); )*
         }
     };
 }
@@ -3,7 +3,7 @@
 use std::{fs, io};
 
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::{config, Day};
 
 static MARKER: &str = "<!--- benchmarking table --->";
 
@@ -55,23 +55,38 @@ fn locate_table(readme: &str) -> Result<TablePosition, Error> {
 fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
     let header = format!("{prefix} Benchmarks");
 
-    let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
-        String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
-    ];
+    let with_alloc = timings.has_alloc_stats();
+
+    let mut lines: Vec<String> = vec![MARKER.into(), header, String::new()];
+
+    if with_alloc {
+        lines.push("| Day | Part 1 | Part 2 | Part 1 memory | Part 2 memory |".into());
+        lines.push("| :---: | :---: | :---:  | :---: | :---: |".into());
+    } else {
+        lines.push("| Day | Part 1 | Part 2 |".into());
+        lines.push("| :---: | :---: | :---:  |".into());
+    }
 
     for timing in timings.data {
         let path = get_path_for_bin(timing.day);
-        lines.push(format!(
+        let mut line = format!(
             "| [Day {}]({}) | `{}` | `{}` |",
             timing.day.into_inner(),
             path,
             timing.part_1.unwrap_or_else(|| "-".into()),
             timing.part_2.unwrap_or_else(|| "-".into())
-        ));
+        );
+
+        if with_alloc {
+            for alloc in [timing.part_1_alloc, timing.part_2_alloc] {
+                match alloc {
+                    Some(stats) => line.push_str(&format!(" `{stats}` |")),
+                    None => line.push_str(" `-` |"),
+                }
+            }
+        }
+
+        lines.push(line);
     }
 
     lines.push(String::new());
@@ -83,13 +98,13 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
 
 fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
     let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
+    let table = construct_table(&config::get().readme.heading, timings, total_millis);
     s.replace_range(positions.pos_start..positions.pos_end, &table);
     Ok(())
 }
 
 pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
+    let path = &config::get().readme.path;
     let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
     let total_millis = timings.total_millis();
     update_content(&mut readme, timings, total_millis)?;
@@ -100,7 +115,10 @@ pub fn update(timings: Timings) -> Result<(), Error> {
 #[cfg(feature = "test_lib")]
 mod tests {
     use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use crate::{
+        day, template::alloc_stats::AllocStats, template::timings::Timing,
+        template::timings::Timings,
+    };
 
     fn get_mock_timings() -> Timings {
         Timings {
@@ -110,20 +128,33 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: Some("50ms".into()),
                     total_nanos: 9e+10,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 },
             ],
+            ..Default::default()
         }
     }
 
@@ -157,6 +188,24 @@ mod tests {
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
 
+    #[test]
+    fn format_benchmarks_with_alloc_stats() {
+        let mut timings = get_mock_timings();
+        timings.data[0].part_1_alloc = Some(AllocStats {
+            peak_bytes: 2048,
+            total_bytes: 4096,
+            allocations: 12,
+        });
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0).unwrap();
+        assert!(s.contains("| Day | Part 1 | Part 2 | Part 1 memory | Part 2 memory |"));
+        assert!(s.contains(
+            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `2.0 KiB peak, 4.0 KiB total, 12 allocs` | `-` |"
+        ));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | `-` | `-` |"));
+    }
+
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
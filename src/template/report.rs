@@ -0,0 +1,511 @@
+/// Module that renders stored timings into a standalone HTML report with inline SVG charts.
+/// The report does not reference any external assets, so it can be attached to PRs as a single file.
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io};
+
+use crate::template::timings::{Timing, Timings};
+use crate::template::Day;
+
+static REPORT_FILE_NAME: &str = "index.html";
+
+const CHART_WIDTH: f64 = 860.0;
+const CHART_HEIGHT: f64 = 280.0;
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 20.0;
+const MARGIN_TOP: f64 = 20.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+
+const COLOR_PART_1: &str = "#9fb4c7";
+const COLOR_PART_2: &str = "#e0b03f";
+const COLOR_LINE: &str = "#57a773";
+
+const SPARKLINE_WIDTH: f64 = 160.0;
+const SPARKLINE_HEIGHT: f64 = 28.0;
+
+/// Write the report for `timings` to `<dir>/index.html`, creating `dir` if necessary.
+pub fn write(dir: &Path, timings: &Timings) -> Result<PathBuf, io::Error> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(REPORT_FILE_NAME);
+    fs::write(&path, render(timings))?;
+    Ok(path)
+}
+
+/// Render the report for `timings` as an HTML document.
+pub fn render(timings: &Timings) -> String {
+    let mut body = String::new();
+
+    let _ = writeln!(body, "<h1>Advent of Code Benchmarks</h1>");
+
+    if timings.data.is_empty() {
+        let _ = writeln!(
+            body,
+            "<p>No timings have been stored yet. Run <code>cargo time --store</code> first.</p>"
+        );
+    } else {
+        let _ = writeln!(
+            body,
+            "<p class=\"total\">Total: <strong>{:.2}ms</strong> across {} day(s).</p>",
+            timings.total_millis(),
+            timings.data.len()
+        );
+
+        let _ = writeln!(body, "<h2>Runtime per part</h2>");
+        let _ = writeln!(
+            body,
+            "<p class=\"hint\">Logarithmic scale. {} Part 1 {} Part 2</p>",
+            legend_swatch(COLOR_PART_1),
+            legend_swatch(COLOR_PART_2)
+        );
+        body.push_str(&part_chart(&timings.data));
+
+        let _ = writeln!(body, "<h2>Cumulative runtime</h2>");
+        body.push_str(&cumulative_chart(&timings.data));
+
+        let _ = writeln!(body, "<h2>Details</h2>");
+        body.push_str(&details_table(&timings.data));
+    }
+
+    if !timings.history.is_empty() {
+        let _ = writeln!(body, "<h2>Run history</h2>");
+        let _ = writeln!(
+            body,
+            "<p class=\"hint\">Total runtime per day over {} stored run(s), oldest to newest.</p>",
+            timings.history.len()
+        );
+        body.push_str(&history_table(timings));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n\
+        <html lang=\"en\">\n\
+        <head>\n\
+        <meta charset=\"utf-8\">\n\
+        <title>Advent of Code Benchmarks</title>\n\
+        <style>{STYLES}</style>\n\
+        </head>\n\
+        <body>\n\
+        {body}\
+        </body>\n\
+        </html>\n"
+    )
+}
+
+static STYLES: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", Helvetica, Arial, sans-serif; \
+margin: 2rem auto; max-width: 900px; color: #1f2328; background: #fff; }\
+h1, h2 { font-weight: 600; }\
+table { border-collapse: collapse; margin-bottom: 1rem; }\
+th, td { padding: 0.25rem 0.75rem; border-bottom: 1px solid #d0d7de; text-align: right; }\
+th:first-child, td:first-child { text-align: left; }\
+code { font-family: ui-monospace, SFMono-Regular, Menlo, monospace; }\
+.hint { color: #656d76; font-size: 0.9rem; }\
+.swatch { display: inline-block; width: 0.8rem; height: 0.8rem; vertical-align: middle; }\
+svg text { font-size: 11px; fill: #656d76; }\
+";
+
+fn legend_swatch(color: &str) -> String {
+    format!("<span class=\"swatch\" style=\"background: {color}\"></span>")
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Grouped bar chart of both parts per day, on a log scale.
+fn part_chart(data: &[Timing]) -> String {
+    let values: Vec<f64> = data
+        .iter()
+        .flat_map(|t| [t.part_1_nanos(), t.part_2_nanos()])
+        .flatten()
+        .filter(|v| *v > 0.0)
+        .collect();
+
+    let min_exp = values
+        .iter()
+        .map(|v| v.log10().floor())
+        .fold(f64::INFINITY, f64::min);
+    let max_exp = values
+        .iter()
+        .map(|v| v.log10().ceil())
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    if !min_exp.is_finite() || !max_exp.is_finite() {
+        return "<p class=\"hint\">No per-part timings available.</p>\n".into();
+    }
+
+    let max_exp = if max_exp <= min_exp {
+        min_exp + 1.0
+    } else {
+        max_exp
+    };
+
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let y = |nanos: f64| {
+        let ratio = (nanos.log10() - min_exp) / (max_exp - min_exp);
+        MARGIN_TOP + plot_height * (1.0 - ratio.clamp(0.0, 1.0))
+    };
+
+    let mut svg = svg_open();
+
+    // NOTE: exponents are small integers here, so the casts are lossless.
+    #[allow(clippy::cast_possible_truncation)]
+    for exp in (min_exp as i32)..=(max_exp as i32) {
+        let nanos = 10_f64.powi(exp);
+        svg.push_str(&y_tick(y(nanos), &format_nanos(nanos)));
+    }
+
+    let slot = slot_width(data.len());
+    let bar = slot * 0.35;
+
+    for (i, timing) in data.iter().enumerate() {
+        let x = slot_x(i, slot);
+
+        for (offset, value, color, part) in [
+            (-bar, timing.part_1_nanos(), COLOR_PART_1, 1),
+            (0.0, timing.part_2_nanos(), COLOR_PART_2, 2),
+        ] {
+            let Some(value) = value.filter(|v| *v > 0.0) else {
+                continue;
+            };
+            let top = y(value);
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{:.1}\" y=\"{top:.1}\" width=\"{bar:.1}\" height=\"{:.1}\" fill=\"{color}\">\
+                <title>Day {} part {part}: {}</title></rect>",
+                x + offset,
+                CHART_HEIGHT - MARGIN_BOTTOM - top,
+                timing.day,
+                format_nanos(value)
+            );
+        }
+
+        svg.push_str(&x_label(x, &timing.day));
+    }
+
+    svg.push_str(&axes());
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Line chart of the runtime accumulated over all days, in milliseconds.
+fn cumulative_chart(data: &[Timing]) -> String {
+    let mut cumulative: Vec<f64> = Vec::with_capacity(data.len());
+    for timing in data {
+        let previous = cumulative.last().copied().unwrap_or_default();
+        cumulative.push(previous + timing.total_nanos / 1_000_000_f64);
+    }
+
+    let max = cumulative.last().copied().unwrap_or_default();
+    let max = if max > 0.0 { max } else { 1.0 };
+
+    let plot_height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let y = |millis: f64| MARGIN_TOP + plot_height * (1.0 - millis / max);
+
+    let mut svg = svg_open();
+
+    for step in 0..=4 {
+        let millis = max * f64::from(step) / 4.0;
+        svg.push_str(&y_tick(y(millis), &format!("{millis:.2}ms")));
+    }
+
+    let slot = slot_width(data.len());
+
+    let points = cumulative
+        .iter()
+        .enumerate()
+        .map(|(i, millis)| format!("{:.1},{:.1}", slot_x(i, slot), y(*millis)))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let _ = writeln!(
+        svg,
+        "<polyline points=\"{points}\" fill=\"none\" stroke=\"{COLOR_LINE}\" stroke-width=\"2\"/>"
+    );
+
+    for (i, (timing, millis)) in data.iter().zip(&cumulative).enumerate() {
+        let x = slot_x(i, slot);
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{x:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{COLOR_LINE}\">\
+            <title>Day {}: {millis:.2}ms</title></circle>",
+            y(*millis),
+            timing.day
+        );
+        svg.push_str(&x_label(x, &timing.day));
+    }
+
+    svg.push_str(&axes());
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn details_table(data: &[Timing]) -> String {
+    let with_alloc = data
+        .iter()
+        .any(|t| t.part_1_alloc.is_some() || t.part_2_alloc.is_some());
+
+    let mut table =
+        String::from("<table>\n<tr><th>Day</th><th>Part 1</th><th>Part 2</th><th>Total</th>");
+    if with_alloc {
+        table.push_str("<th>Part 1 memory</th><th>Part 2 memory</th>");
+    }
+    table.push_str("</tr>\n");
+
+    for timing in data {
+        let _ = write!(
+            table,
+            "<tr><td>Day {}</td><td><code>{}</code></td><td><code>{}</code></td><td><code>{}</code></td>",
+            timing.day.into_inner(),
+            escape(timing.part_1.as_deref().unwrap_or("-")),
+            escape(timing.part_2.as_deref().unwrap_or("-")),
+            format_nanos(timing.total_nanos)
+        );
+
+        if with_alloc {
+            for alloc in [timing.part_1_alloc, timing.part_2_alloc] {
+                let alloc = alloc.map_or_else(|| "-".into(), |stats| stats.to_string());
+                let _ = write!(table, "<td><code>{}</code></td>", escape(&alloc));
+            }
+        }
+
+        table.push_str("</tr>\n");
+    }
+
+    table.push_str("</table>\n");
+    table
+}
+
+/// One row per day with a sparkline of its total runtime across the stored runs.
+fn history_table(timings: &Timings) -> String {
+    let mut days: Vec<Day> = timings
+        .history
+        .iter()
+        .flat_map(|run| run.data.iter().map(|t| t.day))
+        .collect();
+    days.sort_unstable();
+    days.dedup();
+
+    let mut table = String::from(
+        "<table>\n<tr><th>Day</th><th>Runs</th><th>Trend</th><th>First</th><th>Latest</th></tr>\n",
+    );
+
+    for day in days {
+        let series: Vec<f64> = timings
+            .history
+            .iter()
+            .filter_map(|run| run.data.iter().find(|t| t.day == day))
+            .map(|t| t.total_nanos)
+            .collect();
+
+        let (Some(first), Some(latest)) = (series.first(), series.last()) else {
+            continue;
+        };
+
+        let _ = writeln!(
+            table,
+            "<tr><td>Day {}</td><td>{}</td><td>{}</td><td><code>{}</code></td><td><code>{}</code></td></tr>",
+            day.into_inner(),
+            series.len(),
+            sparkline(&series),
+            format_nanos(*first),
+            format_nanos(*latest)
+        );
+    }
+
+    table.push_str("</table>\n");
+    table
+}
+
+fn sparkline(series: &[f64]) -> String {
+    let max = series.iter().copied().fold(0.0, f64::max);
+    let max = if max > 0.0 { max } else { 1.0 };
+
+    #[allow(clippy::cast_precision_loss)]
+    let step = if series.len() > 1 {
+        (SPARKLINE_WIDTH - 4.0) / (series.len() - 1) as f64
+    } else {
+        0.0
+    };
+
+    #[allow(clippy::cast_precision_loss)]
+    let points = series
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            format!(
+                "{:.1},{:.1}",
+                2.0 + step * i as f64,
+                2.0 + (SPARKLINE_HEIGHT - 4.0) * (1.0 - v / max)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SPARKLINE_WIDTH}\" height=\"{SPARKLINE_HEIGHT}\">\
+        <polyline points=\"{points}\" fill=\"none\" stroke=\"{COLOR_LINE}\" stroke-width=\"1.5\"/></svg>"
+    )
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn svg_open() -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{CHART_WIDTH}\" height=\"{CHART_HEIGHT}\" \
+        viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\">\n"
+    )
+}
+
+fn axes() -> String {
+    let bottom = CHART_HEIGHT - MARGIN_BOTTOM;
+    let right = CHART_WIDTH - MARGIN_RIGHT;
+    format!(
+        "<line x1=\"{MARGIN_LEFT}\" y1=\"{MARGIN_TOP}\" x2=\"{MARGIN_LEFT}\" y2=\"{bottom}\" stroke=\"#1f2328\"/>\n\
+        <line x1=\"{MARGIN_LEFT}\" y1=\"{bottom}\" x2=\"{right}\" y2=\"{bottom}\" stroke=\"#1f2328\"/>\n"
+    )
+}
+
+fn y_tick(y: f64, label: &str) -> String {
+    let right = CHART_WIDTH - MARGIN_RIGHT;
+    format!(
+        "<line x1=\"{MARGIN_LEFT}\" y1=\"{y:.1}\" x2=\"{right}\" y2=\"{y:.1}\" stroke=\"#d0d7de\"/>\n\
+        <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n",
+        MARGIN_LEFT - 6.0,
+        y + 4.0,
+        escape(label)
+    )
+}
+
+fn x_label(x: f64, day: &Day) -> String {
+    format!(
+        "<text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{day}</text>\n",
+        CHART_HEIGHT - MARGIN_BOTTOM + 16.0
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn slot_width(len: usize) -> f64 {
+    (CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT) / len.max(1) as f64
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn slot_x(index: usize, slot: f64) -> f64 {
+    MARGIN_LEFT + slot * (index as f64 + 0.5)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos.max(0.0) as u64))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::render;
+    use crate::{
+        day,
+        template::timings::{Timing, Timings, TimingsRun},
+    };
+
+    fn get_mock_timings() -> Timings {
+        Timings {
+            data: vec![
+                Timing {
+                    day: day!(1),
+                    part_1: Some("10ms".into()),
+                    part_2: Some("20µs".into()),
+                    total_nanos: 10_020_000_f64,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
+                },
+                Timing {
+                    day: day!(2),
+                    part_1: Some("30ns".into()),
+                    part_2: None,
+                    total_nanos: 30_f64,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
+                },
+            ],
+            history: vec![
+                TimingsRun {
+                    timestamp: 1_700_000_000,
+                    data: vec![Timing {
+                        day: day!(1),
+                        part_1: Some("20ms".into()),
+                        part_2: Some("20µs".into()),
+                        total_nanos: 20_020_000_f64,
+                        part_1_alloc: None,
+                        part_2_alloc: None,
+                        part_1_error: None,
+                        part_2_error: None,
+                    }],
+                },
+                TimingsRun {
+                    timestamp: 1_700_000_100,
+                    data: vec![Timing {
+                        day: day!(1),
+                        part_1: Some("10ms".into()),
+                        part_2: Some("20µs".into()),
+                        total_nanos: 10_020_000_f64,
+                        part_1_alloc: None,
+                        part_2_alloc: None,
+                        part_1_error: None,
+                        part_2_error: None,
+                    }],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_self_contained_document() {
+        let html = render(&get_mock_timings());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("src=\""));
+    }
+
+    #[test]
+    fn renders_charts_for_each_day() {
+        let html = render(&get_mock_timings());
+        assert_eq!(html.matches("<h2>").count(), 4);
+        assert!(html.contains("<title>Day 01 part 1: 10.0ms</title>"));
+        assert!(html.contains("<title>Day 01 part 2: 20.0µs</title>"));
+        assert!(html.contains("<title>Day 02 part 1: 30.0ns</title>"));
+        assert!(!html.contains("Day 02 part 2"));
+        assert!(html.contains("<title>Day 02: 10.02ms</title>"));
+    }
+
+    #[test]
+    fn renders_history_when_available() {
+        let html = render(&get_mock_timings());
+        assert!(html.contains("<h2>Run history</h2>"));
+        assert!(html.contains("<td>Day 1</td><td>2</td>"));
+
+        let mut timings = get_mock_timings();
+        timings.history.clear();
+        let html = render(&timings);
+        assert!(!html.contains("<h2>Run history</h2>"));
+    }
+
+    #[test]
+    fn renders_empty_timings() {
+        let html = render(&Timings::default());
+        assert!(html.contains("No timings have been stored yet."));
+        assert!(!html.contains("<svg"));
+    }
+}
@@ -1,13 +1,19 @@
 use std::{collections::HashSet, io};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{runner::RunOptions, Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
     all_days,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    with_alloc_stats: bool,
+    options: &RunOptions,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
@@ -24,7 +30,9 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output =
+                child_commands::run_solution(day, is_timed, is_release, with_alloc_stats, options)
+                    .unwrap();
 
             if output.is_empty() {
                 println!("Not solved.");
@@ -34,8 +42,13 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             }
         });
 
+    print_failures(&timings);
+
     if is_timed {
-        let timings = Timings { data: timings };
+        let timings = Timings {
+            data: timings,
+            ..Default::default()
+        };
         let total_millis = timings.total_millis();
         println!(
             "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
@@ -46,6 +59,27 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
     }
 }
 
+/// Summarize all parts that returned an error, panicked or timed out, so they do not get lost in the output.
+fn print_failures(timings: &[Timing]) {
+    let failures: Vec<_> = timings
+        .iter()
+        .flat_map(|t| {
+            [(1, &t.part_1_error), (2, &t.part_2_error)]
+                .into_iter()
+                .filter_map(move |(part, error)| Some((t.day, part, error.as_ref()?)))
+        })
+        .collect();
+
+    if failures.is_empty() {
+        return;
+    }
+
+    println!("\n{ANSI_BOLD}Failures:{ANSI_RESET}");
+    for (day, part, error) in failures {
+        println!("Day {day}, part {part}: {error}");
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum Error {
@@ -68,38 +102,54 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::{
+        alloc_stats::AllocStats, runner::RunOptions, timings::parse_duration_nanos, Day,
+    };
     use std::{
+        collections::HashMap,
         io::{BufRead, BufReader},
-        path::Path,
-        process::{Command, Stdio},
+        path::{Path, PathBuf},
+        process::{Child, Command, Stdio},
+        str::FromStr,
         thread,
+        time::{Duration, Instant},
     };
+    use tinyjson::JsonValue;
+
+    /// Extra time a solution binary gets on top of the time its parts are allowed to take.
+    const CHILD_GRACE: Duration = Duration::from_secs(2);
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        with_alloc_stats: bool,
+        options: &RunOptions,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        // NOTE: the binary is built and invoked separately instead of using `cargo run`, so that no compile time
+        // counts towards the timeout and killing the child does not leave an orphaned solution process behind.
+        let Some(executable) = build_solution(day, is_release, with_alloc_stats)? else {
+            // compiler errors have already been forwarded to stderr.
+            return Ok(vec![]);
+        };
 
-        if is_release {
-            args.push("--release");
-        }
+        let mut args = options.to_args();
 
         if is_timed {
             // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            args.push("--time".into());
         }
 
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
-        let mut cmd = Command::new("cargo")
+        let mut cmd = Command::new(executable)
             .args(&args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -116,6 +166,11 @@ pub mod child_commands {
             });
         });
 
+        let deadline = options
+            .timeout
+            .map(|timeout| child_deadline(timeout, is_timed));
+        let watchdog = thread::spawn(move || wait_with_deadline(&mut cmd, deadline));
+
         for line in stdout.lines() {
             let line = line.unwrap();
             println!("{line}");
@@ -123,17 +178,116 @@ pub mod child_commands {
         }
 
         thread.join().unwrap();
-        cmd.wait()?;
+
+        if let Some(deadline) = watchdog.join().unwrap()? {
+            let line = format!("✖ timed out after {deadline:.1?}, killed.");
+            println!("{line}");
+            output.push(line);
+        }
 
         Ok(output)
     }
 
+    /// Build the solution bin for a given day and return the path of its executable if successful.
+    fn build_solution(
+        day: Day,
+        is_release: bool,
+        with_alloc_stats: bool,
+    ) -> Result<Option<PathBuf>, Error> {
+        let day_padded = day.to_string();
+        let mut args = vec![
+            "build",
+            "--quiet",
+            "--bin",
+            &day_padded,
+            "--message-format=json-render-diagnostics",
+        ];
+
+        if is_release {
+            args.push("--release");
+        }
+
+        if with_alloc_stats {
+            args.extend(["--features", "alloc-stats"]);
+        }
+
+        let output = Command::new("cargo")
+            .args(&args)
+            .stderr(Stdio::inherit())
+            .output()?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(parse_executable)
+            .map(PathBuf::from))
+    }
+
+    /// Extracts the executable path from a cargo `compiler-artifact` JSON message.
+    fn parse_executable(line: &str) -> Option<String> {
+        let json = JsonValue::from_str(line).ok()?;
+        let message = json.get::<HashMap<String, JsonValue>>()?;
+
+        if message.get("reason")?.get::<String>()? != "compiler-artifact" {
+            return None;
+        }
+
+        message.get("executable")?.get::<String>().cloned()
+    }
+
+    /// Upper bound for the runtime of a solution binary whose parts are limited by `timeout`.
+    /// The timeout applies to the first run of each part, benching repeats it at most ten more times or for about a second.
+    fn child_deadline(timeout: Duration, is_timed: bool) -> Duration {
+        let per_part = if is_timed {
+            timeout * 11 + Duration::from_secs(1)
+        } else {
+            timeout
+        };
+
+        per_part * 2 + CHILD_GRACE
+    }
+
+    /// Wait for `child` to exit, killing it once `deadline` is exceeded.
+    /// Returns the deadline if the child had to be killed.
+    pub fn wait_with_deadline(
+        child: &mut Child,
+        deadline: Option<Duration>,
+    ) -> Result<Option<Duration>, Error> {
+        let Some(deadline) = deadline else {
+            child.wait()?;
+            return Ok(None);
+        };
+
+        let timer = Instant::now();
+
+        loop {
+            if child.try_wait()?.is_some() {
+                return Ok(None);
+            }
+
+            if timer.elapsed() > deadline {
+                child.kill()?;
+                child.wait()?;
+                return Ok(Some(deadline));
+            }
+
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
             part_1: None,
             part_2: None,
             total_nanos: 0_f64,
+            part_1_alloc: None,
+            part_2_alloc: None,
+            part_1_error: None,
+            part_2_error: None,
         };
 
         output
@@ -161,30 +315,99 @@ pub mod child_commands {
                 timings.total_nanos += nanos;
             });
 
+        output
+            .iter()
+            .filter_map(|l| Some((l.split(':').next()?, parse_alloc(l)?)))
+            .for_each(|(part, alloc)| {
+                if part.contains("Part 1") {
+                    timings.part_1_alloc = Some(alloc);
+                } else if part.contains("Part 2") {
+                    timings.part_2_alloc = Some(alloc);
+                }
+            });
+
+        parse_failures(output, &mut timings);
+
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    /// Records parts that returned an error, panicked or timed out.
+    /// If the whole binary was killed, parts that did not report anything are marked as timed out.
+    fn parse_failures(output: &[String], timings: &mut super::Timing) {
+        let mut reported = [false; 2];
+
+        for (i, line) in output.iter().enumerate() {
+            // NOTE: the runner overwrites its "Part N: ⏳" progress indicator using carriage returns.
+            let line = line.rsplit('\r').next().unwrap_or(line);
+
+            if let Some(message) = line.strip_prefix("✖ ") {
+                for (part, reported) in reported.iter_mut().enumerate() {
+                    if !*reported {
+                        *reported = true;
+                        set_error(timings, part + 1, message.to_string());
+                    }
+                }
+                continue;
+            }
+
+            let Some((part, result)) = line.split_once(": ") else {
+                continue;
+            };
+
+            let part = match part {
+                "Part 1" => 1,
+                "Part 2" => 2,
+                _ => continue,
+            };
+
+            reported[part - 1] = true;
+
+            let Some(message) = result.trim().strip_prefix('✖') else {
+                continue;
+            };
+
+            let message = match message.trim() {
+                // multi-line errors are printed below the part.
+                "▼" => output.get(i + 1).map_or("", |l| l.trim()),
+                message => message,
+            };
+
+            // parts returning `None` are not solved yet, which is not a failure.
+            if !message.is_empty() {
+                set_error(timings, part, message.to_string());
+            }
+        }
+    }
+
+    fn set_error(timings: &mut super::Timing, part: usize, message: String) {
+        if part == 1 {
+            timings.part_1_error = Some(message);
+        } else {
+            timings.part_2_error = Some(message);
+        }
+    }
+
+    /// Parses the allocation stats appended to a part's output by the `alloc-stats` feature.
+    fn parse_alloc(line: &str) -> Option<AllocStats> {
+        if !line.starts_with("Part ") {
+            return None;
+        }
+
+        let (_, stats) = line.strip_suffix(']')?.rsplit_once(" [")?;
+        stats.parse().ok()
     }
 
     fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
         let str_timing = line
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
+        let parsed_timing = parse_duration_nanos(str_timing)?;
 
         Some((str_timing, parsed_timing))
     }
@@ -205,7 +428,8 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{child_deadline, parse_exec_time, parse_executable};
+        use std::time::Duration;
 
         use crate::day;
 
@@ -239,6 +463,26 @@ pub mod child_commands {
             assert_eq!(res.part_2.unwrap(), "100ms");
         }
 
+        #[test]
+        fn parses_alloc_stats() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples) [1.0 KiB peak, 2.0 KiB total, 3 allocs]"
+                        .into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 74130074.13_f64);
+            assert_eq!(res.part_1.unwrap(), "74.13ns");
+            let alloc = res.part_1_alloc.unwrap();
+            assert_eq!(alloc.peak_bytes, 1024);
+            assert_eq!(alloc.total_bytes, 2048);
+            assert_eq!(alloc.allocations, 3);
+            assert_eq!(res.part_2_alloc.is_none(), true);
+        }
+
         #[test]
         fn parses_missing_parts() {
             let res = parse_exec_time(
@@ -252,6 +496,63 @@ pub mod child_commands {
             assert_approx_eq!(res.total_nanos, 0_f64);
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
+            assert_eq!(res.part_1_error.is_none(), true);
+        }
+
+        #[test]
+        fn parses_failures() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: ⏳\rPart 1: ✖ panicked: oh no".into(),
+                    "Part 2: ✖ ▼".into(),
+                    "invalid digit found in string".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_eq!(res.part_1_error, Some("panicked: oh no".into()));
+            assert_eq!(
+                res.part_2_error,
+                Some("invalid digit found in string".into())
+            );
+        }
+
+        #[test]
+        fn parses_killed_solutions() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
+                    "✖ timed out after 4.0s, killed.".into(),
+                ],
+                day!(1),
+            );
+            assert_eq!(res.part_1.unwrap(), "74.13ns");
+            assert_eq!(res.part_1_error, None);
+            assert_eq!(
+                res.part_2_error,
+                Some("timed out after 4.0s, killed.".into())
+            );
+        }
+
+        #[test]
+        fn parses_executable_from_cargo_messages() {
+            let artifact = r#"{"reason":"compiler-artifact","target":{"name":"01"},"executable":"/tmp/target/release/01","fresh":true}"#;
+            let lib = r#"{"reason":"compiler-artifact","target":{"name":"advent_of_code"},"executable":null,"fresh":true}"#;
+            let finished = r#"{"reason":"build-finished","success":true}"#;
+            assert_eq!(
+                parse_executable(artifact),
+                Some("/tmp/target/release/01".into())
+            );
+            assert_eq!(parse_executable(lib), None);
+            assert_eq!(parse_executable(finished), None);
+            assert_eq!(parse_executable("not json"), None);
+        }
+
+        #[test]
+        fn allows_benching_within_deadline() {
+            let timeout = Duration::from_secs(1);
+            assert_eq!(child_deadline(timeout, false), Duration::from_secs(4));
+            assert_eq!(child_deadline(timeout, true), Duration::from_secs(26));
         }
     }
 }
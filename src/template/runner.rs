@@ -1,41 +1,249 @@
 /// Encapsulates code that interacts with solution functions.
+use std::any::Any;
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
 use std::process::Output;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::parse::ParseError;
+use crate::template::alloc_stats::AllocStats;
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, config, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Stack size of the threads that solution parts run on, unless overridden with `--stack-size`.
+pub const DEFAULT_STACK_SIZE_MIB: usize = 64;
+
+/// Options that control how solution parts are run.
+/// They are passed from the `solve`, `all` and `time` commands to the solution binaries as arguments.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RunOptions {
+    /// Wall-clock limit for a single run of a part.
+    pub timeout: Option<Duration>,
+    /// Stack size of the thread a part runs on, in MiB.
+    pub stack_size_mib: Option<usize>,
+}
+
+impl RunOptions {
+    /// Read the options from the arguments of the current process.
+    pub fn from_env() -> Self {
+        let args: Vec<String> = env::args().collect();
+        let config = config::get();
+
+        let value_of = |name: &str| {
+            args.iter()
+                .position(|x| x == name)
+                .and_then(|i| args.get(i + 1))
+        };
+
+        let invalid = |name: &str, e: &dyn Display| -> ! {
+            eprintln!("Invalid value for \"{name}\": {e}.");
+            process::exit(1);
+        };
+
+        Self {
+            timeout: value_of("--timeout")
+                .map(|x| parse_timeout(x).unwrap_or_else(|e| invalid("--timeout", &e)))
+                .or(config.run.timeout),
+            stack_size_mib: value_of("--stack-size")
+                .map(|x| x.parse().unwrap_or_else(|e| invalid("--stack-size", &e)))
+                .or(config.run.stack_size_mib),
+        }
+    }
+
+    /// Arguments that forward these options to a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        if let Some(timeout) = self.timeout {
+            args.push("--timeout".into());
+            args.push(timeout.as_secs_f64().to_string());
+        }
+
+        if let Some(stack_size_mib) = self.stack_size_mib {
+            args.push("--stack-size".into());
+            args.push(stack_size_mib.to_string());
+        }
+
+        args
+    }
+
+    fn stack_size(&self) -> usize {
+        self.stack_size_mib.unwrap_or(DEFAULT_STACK_SIZE_MIB) * 1024 * 1024
+    }
+}
+
+/// Parses a timeout given in (fractional) seconds.
+pub fn parse_timeout(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .filter(|timeout| !timeout.is_zero())
+        .ok_or_else(|| format!("expecting a positive number of seconds, found `{s}`"))
+}
+
+/// Reasons why a solution part did not produce a result.
+#[derive(Debug)]
+enum Failure {
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failure::Panicked(msg) => write!(f, "panicked: {msg}"),
+            Failure::TimedOut(timeout) => write!(f, "timed out after {timeout:.1?}"),
+        }
+    }
+}
+
+/// Return types that solution parts may use.
+///
+/// Parts either return an `Option`, where `None` means that the part is not solved yet, or a `Result`, where the
+/// error explains why there is no answer.
+pub trait PartResult {
+    type Answer: Display;
+
+    /// Converts the value into the answer, or the message to show in its place (if any).
+    ///
+    /// # Errors
+    /// If the part did not produce an answer.
+    fn into_answer(self) -> Result<Self::Answer, Option<String>>;
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<T, Option<String>> {
+        self.ok_or(None)
+    }
+}
+
+impl<T: Display, E: Display + 'static> PartResult for Result<T, E> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<T, Option<String>> {
+        self.map_err(|e| {
+            // NOTE: `anyhow` does not pass the alternate flag on, so parse errors would lose their excerpt.
+            let parse_error = (&e as &dyn Any)
+                .downcast_ref::<anyhow::Error>()
+                .and_then(|e| e.downcast_ref::<ParseError>());
+            // NOTE: the alternate form prints the whole chain of causes for `anyhow` errors.
+            Some(match parse_error {
+                Some(parse_error) => format!("{parse_error:#}"),
+                None => format!("{e:#}"),
+            })
+        })
+    }
+}
+
+pub fn run_part<I, R>(func: impl Fn(I) -> R + Send + 'static, input: I, day: Day, part: u8)
+where
+    I: Copy + Send + 'static,
+    R: PartResult,
+    R::Answer: Send + 'static,
+{
     let part_str = format!("Part {part}");
+    let options = RunOptions::from_env();
+
+    let hook_part_str = part_str.clone();
+    let outcome = run_isolated(part, &options, move |solved| {
+        run_timed(
+            move |i| func(i).into_answer(),
+            input,
+            |result| {
+                print_result(result, &hook_part_str, "");
+                // NOTE: the receiver is gone if the part already timed out.
+                let _ = solved.send(());
+            },
+        )
+    });
+
+    let (result, duration, samples, alloc) = match outcome {
+        Ok(run) => run,
+        Err(failure) => {
+            print_failure(&part_str, &failure);
+            return;
+        }
+    };
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let stats_str = format!(
+        "{}{}",
+        format_duration(&duration, samples),
+        format_alloc(alloc.as_ref())
+    );
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(&result, &part_str, &stats_str);
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    #[cfg(feature = "visualize")]
+    crate::visualize::show(day, part);
+
+    if let Ok(result) = result {
+        submit_result(&result, day, part);
+    }
+}
+
+/// Run `func` on a dedicated thread, so that deep recursion gets a large enough stack and panics do not take down the
+/// other parts. `func` has to signal through the passed sender once the part was solved for the first time, the
+/// timeout only applies up to that point so that benching is not cut short.
+///
+/// A timed out thread can not be stopped, it keeps running in the background until the process exits.
+fn run_isolated<R: Send + 'static>(
+    part: u8,
+    options: &RunOptions,
+    func: impl FnOnce(mpsc::Sender<()>) -> R + Send + 'static,
+) -> Result<R, Failure> {
+    let (solved_tx, solved_rx) = mpsc::channel();
+
+    let handle = thread::Builder::new()
+        .name(format!("part-{part}"))
+        .stack_size(options.stack_size())
+        .spawn(move || func(solved_tx))
+        .expect("could not spawn thread for solution part");
+
+    if let Some(timeout) = options.timeout {
+        match solved_rx.recv_timeout(timeout) {
+            // the sender is dropped without a signal if the thread panicked.
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => {}
+            Err(RecvTimeoutError::Timeout) => return Err(Failure::TimedOut(timeout)),
+        }
+    }
+
+    handle
+        .join()
+        .map_err(|payload| Failure::Panicked(panic_message(payload.as_ref())))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        (*msg).to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic payload".into()
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///
+/// With the `alloc-stats` feature, the allocations of the first execution are recorded as well.
 fn run_timed<I: Copy, T>(
     func: impl Fn(I) -> T,
     input: I,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+) -> (T, Duration, u128, Option<AllocStats>) {
     let timer = Instant::now();
-    let result = {
+    let (result, alloc) = {
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        measure_alloc(|| func(input))
     };
     let base_time = timer.elapsed();
 
@@ -47,7 +255,18 @@ fn run_timed<I: Copy, T>(
         (base_time, 1)
     };
 
-    (result, run.0, run.1)
+    (result, run.0, run.1, alloc)
+}
+
+#[cfg(feature = "alloc-stats")]
+fn measure_alloc<T>(func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    let (result, stats) = crate::template::alloc_stats::measure(func);
+    (result, Some(stats))
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+fn measure_alloc<T>(func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    (func(), None)
 }
 
 fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
@@ -90,13 +309,27 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn format_alloc(alloc: Option<&AllocStats>) -> String {
+    alloc.map(|stats| format!(" [{stats}]")).unwrap_or_default()
+}
+
+fn print_failure(part: &str, failure: &Failure) {
+    print!("\r");
+    println!("{part}: ✖ {failure}");
+}
+
+fn print_result<T: Display>(result: &Result<T, Option<String>>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
-            if result.to_string().contains('\n') {
-                let str = format!("{part}: ▼ {duration_str}");
+        Ok(result) => {
+            let result_str = result.to_string();
+            if result_str.contains('\n') {
+                // show the letters drawn by the answer, if they are recognized.
+                let letters = crate::ocr::parse_letters(&result_str)
+                    .map(|letters| format!("{ANSI_BOLD}{letters}{ANSI_RESET}"))
+                    .unwrap_or_default();
+                let str = format!("{part}: ▼ {letters}{duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
                 } else {
@@ -114,7 +347,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Err(None) => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -122,14 +355,28 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        Err(Some(msg)) => {
+            if is_intermediate_result {
+                print!("{part}: ✖");
+            } else if msg.contains('\n') {
+                print!("\r");
+                println!("{part}: ✖ ▼");
+                println!("{msg}");
+            } else {
+                print!("\r");
+                println!("{part}: ✖ {msg}");
+            }
+        }
     }
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
+///
+/// Answers drawn as block letters are submitted as the letters they spell.
 fn submit_result<T: Display>(
-    result: T,
+    result: &T,
     day: Day,
     part: u8,
 ) -> Option<Result<Output, aoc_cli::AocCommandError>> {
@@ -160,6 +407,163 @@ fn submit_result<T: Display>(
         process::exit(1);
     }
 
+    let Some(answer) = submission(&result.to_string()) else {
+        eprintln!("Could not recognize the letters drawn by the answer of part {part}, please submit it manually.");
+        process::exit(1);
+    };
+
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(aoc_cli::submit(day, part, &answer))
+}
+
+/// The answer to submit for a result, decoding multi-line results as block letters.
+fn submission(result: &str) -> Option<String> {
+    if result.contains('\n') {
+        crate::ocr::parse_letters(result)
+    } else {
+        Some(result.to_string())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_timeout, run_isolated, submission, Failure, PartResult, RunOptions};
+    use std::time::Duration;
+
+    #[test]
+    fn converts_options_into_answers() {
+        assert_eq!(Some(42).into_answer(), Ok(42));
+        assert_eq!(None::<u64>.into_answer(), Err(None));
+    }
+
+    #[test]
+    fn converts_results_into_answers() {
+        assert_eq!(Ok::<u64, String>(42).into_answer(), Ok(42));
+        assert_eq!(
+            Err::<u64, _>("no bag found").into_answer(),
+            Err(Some("no bag found".into()))
+        );
+    }
+
+    #[test]
+    fn points_at_parse_errors() {
+        let error = crate::parse::lines::<u64>("1\n2x").unwrap_err();
+        assert_eq!(
+            Err::<u64, _>(error).into_answer(),
+            Err(Some(
+                "invalid digit found in string\n --> line 2, column 1\n  |\n2 | 2x\n  | ^^".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn points_at_wrapped_parse_errors() {
+        let input = "1\n2x";
+        let error = anyhow::Error::from(crate::parse::lines::<u64>(input).unwrap_err());
+        assert_eq!(
+            Err::<u64, _>(error).into_answer(),
+            Err(Some(
+                "invalid digit found in string\n --> line 2, column 1\n  |\n2 | 2x\n  | ^^".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn includes_error_chain() {
+        let err = anyhow::anyhow!("invalid digit").context("could not parse line 3");
+        assert_eq!(
+            Err::<u64, _>(err).into_answer(),
+            Err(Some("could not parse line 3: invalid digit".into()))
+        );
+    }
+
+    #[test]
+    fn submits_letters_of_drawn_answers() {
+        assert_eq!(submission("1234"), Some("1234".into()));
+        let art = "\n#..#\n#..#\n####\n#..#\n#..#\n#..#\n";
+        assert_eq!(submission(art), Some("H".into()));
+        assert_eq!(submission("#\n#"), None);
+    }
+
+    #[test]
+    fn parses_timeouts() {
+        assert_eq!(parse_timeout("2"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_timeout("0.5"), Ok(Duration::from_millis(500)));
+        assert!(parse_timeout("0").is_err());
+        assert!(parse_timeout("-1").is_err());
+        assert!(parse_timeout("soon").is_err());
+    }
+
+    #[test]
+    fn forwards_options_as_args() {
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(1500)),
+            stack_size_mib: Some(256),
+        };
+        assert_eq!(
+            options.to_args(),
+            vec!["--timeout", "1.5", "--stack-size", "256"]
+        );
+        assert_eq!(RunOptions::default().to_args().len(), 0);
+    }
+
+    #[test]
+    fn returns_result_of_isolated_run() {
+        let result = run_isolated(1, &RunOptions::default(), |solved| {
+            solved.send(()).unwrap();
+            42
+        });
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn reports_panics() {
+        let result = run_isolated(1, &RunOptions::default(), |_| -> u64 { panic!("oh no") });
+        match result {
+            Err(Failure::Panicked(msg)) => assert_eq!(msg, "oh no"),
+            _ => panic!("expected a panic to be reported"),
+        }
+    }
+
+    #[test]
+    fn reports_timeouts() {
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(20)),
+            ..Default::default()
+        };
+        let result = run_isolated(1, &options, |_| loop {
+            std::thread::sleep(Duration::from_millis(10));
+        });
+        assert!(matches!(result, Err(Failure::TimedOut(_))));
+    }
+
+    #[test]
+    fn does_not_time_out_after_solving() {
+        let options = RunOptions {
+            timeout: Some(Duration::from_millis(20)),
+            ..Default::default()
+        };
+        let result = run_isolated(1, &options, |solved| {
+            solved.send(()).unwrap();
+            // e.g. benching
+            std::thread::sleep(Duration::from_millis(50));
+            1
+        });
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[test]
+    fn runs_deep_recursion() {
+        fn depth(n: u64) -> u64 {
+            let padding = std::hint::black_box([0u8; 512]);
+            if n == 0 {
+                u64::from(padding[0])
+            } else {
+                1 + depth(n - 1)
+            }
+        }
+
+        let result = run_isolated(1, &RunOptions::default(), |_| depth(50_000));
+        assert_eq!(result.unwrap(), 50_000);
+    }
 }
@@ -1,9 +1,16 @@
-use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Error,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
+use crate::template::{alloc_stats::AllocStats, config, Day};
 
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+/// Number of runs that are kept in the history of the timings file.
+const MAX_HISTORY: usize = 20;
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
@@ -12,6 +19,48 @@ pub struct Timing {
     pub part_1: Option<String>,
     pub part_2: Option<String>,
     pub total_nanos: f64,
+    /// Allocations of part one, if it was run with the `alloc-stats` feature.
+    pub part_1_alloc: Option<AllocStats>,
+    /// Allocations of part two, if it was run with the `alloc-stats` feature.
+    pub part_2_alloc: Option<AllocStats>,
+    /// Why part one failed, if it returned an error, panicked or timed out.
+    pub part_1_error: Option<String>,
+    /// Why part two failed, if it returned an error, panicked or timed out.
+    pub part_2_error: Option<String>,
+}
+
+impl Timing {
+    /// Duration of part one in nanoseconds, if it has been benched.
+    pub fn part_1_nanos(&self) -> Option<f64> {
+        self.part_1.as_deref().and_then(parse_duration_nanos)
+    }
+
+    /// Duration of part two in nanoseconds, if it has been benched.
+    pub fn part_2_nanos(&self) -> Option<f64> {
+        self.part_2.as_deref().and_then(parse_duration_nanos)
+    }
+
+    /// This timing, with the parts that failed replaced by their `stored` timing.
+    fn keep_stored_failures(&self, stored: &Timing) -> Timing {
+        if self.part_1_error.is_none() && self.part_2_error.is_none() {
+            return self.clone();
+        }
+
+        let mut timing = self.clone();
+        if self.part_1_error.is_some() && stored.part_1.is_some() {
+            timing.part_1.clone_from(&stored.part_1);
+            timing.part_1_alloc = stored.part_1_alloc;
+            timing.part_1_error.clone_from(&stored.part_1_error);
+        }
+        if self.part_2_error.is_some() && stored.part_2.is_some() {
+            timing.part_2.clone_from(&stored.part_2);
+            timing.part_2_alloc = stored.part_2_alloc;
+            timing.part_2_error.clone_from(&stored.part_2_error);
+        }
+        timing.total_nanos =
+            timing.part_1_nanos().unwrap_or_default() + timing.part_2_nanos().unwrap_or_default();
+        timing
+    }
 }
 
 /// Represents benchmark times for a set of days.
@@ -19,30 +68,44 @@ pub struct Timing {
 #[derive(Clone, Debug, Default)]
 pub struct Timings {
     pub data: Vec<Timing>,
+    /// Previous benchmark runs, oldest first.
+    pub history: Vec<TimingsRun>,
+}
+
+/// The timings of the days benched by a single stored `time` invocation.
+#[derive(Clone, Debug)]
+pub struct TimingsRun {
+    /// Unix timestamp (in seconds) of the run.
+    pub timestamp: u64,
+    pub data: Vec<Timing>,
 }
 
 impl Timings {
     /// Dehydrate timings to a JSON file.
     pub fn store_file(&self) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(&config::get().timings_file)?;
         json.format_to(&mut file)
     }
 
     /// Rehydrate timings from a JSON file. If not present, returns empty timings.
     pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+        fs::read_to_string(&config::get().timings_file)
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
     }
 
     /// Merge two sets of timings, overwriting `self` with `other` if present.
+    /// Parts that failed in `other` keep their timing from `self`, so that a flaky run does not lose a benchmark.
     pub fn merge(&self, new: &Self) -> Self {
         let mut data: Vec<Timing> = vec![];
 
         for timing in &new.data {
-            data.push(timing.clone());
+            match self.data.iter().find(|t| t.day == timing.day) {
+                Some(stored) => data.push(timing.keep_stored_failures(stored)),
+                None => data.push(timing.clone()),
+            }
         }
 
         for timing in &self.data {
@@ -51,8 +114,35 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
-        Timings { data }
+        data.sort_unstable_by_key(|a| a.day);
+
+        let mut history = self.history.clone();
+        history.extend(new.history.iter().cloned());
+
+        let mut timings = Timings { data, history };
+        timings.truncate_history();
+        timings
+    }
+
+    /// Append the given timings to the run history, stamped with the current time.
+    pub fn push_history(&mut self, data: Vec<Timing>) {
+        if data.is_empty() {
+            return;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        self.history.push(TimingsRun { timestamp, data });
+        self.truncate_history();
+    }
+
+    /// Drops the oldest runs, so that the timings file does not grow with every stored run.
+    fn truncate_history(&mut self) {
+        let excess = self.history.len().saturating_sub(MAX_HISTORY);
+        self.history.drain(..excess);
     }
 
     /// Sum up total duration of timings as millis.
@@ -60,13 +150,54 @@ impl Timings {
         self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
     }
 
-    pub fn is_day_complete(&self, day: Day) -> bool {
+    pub fn is_day_complete(&self, day: &Day) -> bool {
+        self.data
+            .iter()
+            .any(|t| t.day == *day && t.part_1.is_some() && t.part_2.is_some())
+    }
+
+    /// When the stored timing of `day` was benched, if there is one.
+    /// Timings stored before the run history was recorded fall back to the time the timings file was written.
+    pub fn benched_at(&self, day: Day) -> Option<SystemTime> {
+        if !self.data.iter().any(|t| t.day == day) {
+            return None;
+        }
+
+        self.history
+            .iter()
+            .rev()
+            .find(|run| run.data.iter().any(|t| t.day == day))
+            .map(|run| UNIX_EPOCH + Duration::from_secs(run.timestamp))
+            .or_else(|| {
+                fs::metadata(&config::get().timings_file)
+                    .and_then(|m| m.modified())
+                    .ok()
+            })
+    }
+
+    /// Whether any of the timings contains allocation stats.
+    pub fn has_alloc_stats(&self) -> bool {
         self.data
             .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+            .any(|t| t.part_1_alloc.is_some() || t.part_2_alloc.is_some())
+    }
+}
+
+/// Parses a duration as printed by `Duration`'s debug formatter (e.g. `74.13µs`) into nanoseconds.
+pub fn parse_duration_nanos(s: &str) -> Option<f64> {
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    match s.trim() {
+        s if s.contains("ns") => parse_to_float(s, "ns"),
+        s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
+        s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
     }
 }
 
+fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
+    s.split(postfix).next()?.parse().ok()
+}
+
 /* -------------------------------------------------------------------------- */
 
 impl From<Timings> for JsonValue {
@@ -78,6 +209,11 @@ impl From<Timings> for JsonValue {
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
         );
 
+        map.insert(
+            "history".into(),
+            JsonValue::Array(value.history.iter().map(JsonValue::from).collect()),
+        );
+
         JsonValue::Object(map)
     }
 }
@@ -88,25 +224,85 @@ impl TryFrom<String> for Timings {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
 
-        let json_data = json
+        let json = json
             .get::<HashMap<String, JsonValue>>()
-            .ok_or("expected JSON document to be an object.")?
+            .ok_or("expected JSON document to be an object.")?;
+
+        let json_data = json
             .get("data")
             .ok_or("expected JSON document to have key `data`.")?
             .get::<Vec<JsonValue>>()
             .ok_or("expected `json.data` to be an array.")?;
 
+        // NOTE: timings files written by older versions do not contain a history.
+        let history = match json.get("history") {
+            Some(v) => v
+                .get::<Vec<JsonValue>>()
+                .ok_or("expected `json.history` to be an array.")?
+                .iter()
+                .map(TimingsRun::try_from)
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+
         Ok(Timings {
             data: json_data
                 .iter()
                 .map(Timing::try_from)
                 .collect::<Result<_, _>>()?,
+            history,
         })
     }
 }
 
 /* -------------------------------------------------------------------------- */
 
+impl From<&TimingsRun> for JsonValue {
+    fn from(value: &TimingsRun) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "timestamp".into(),
+            JsonValue::Number(value.timestamp as f64),
+        );
+        map.insert(
+            "data".into(),
+            JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
+        );
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for TimingsRun {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected history entry to be a JSON object.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let timestamp = json
+            .get("timestamp")
+            .and_then(|v| v.get::<f64>().copied())
+            .ok_or("Expected history.timestamp to be a number.")? as u64;
+
+        let data = json
+            .get("data")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .ok_or("Expected history.data to be an array.")?
+            .iter()
+            .map(Timing::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(TimingsRun { timestamp, data })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 impl From<&Timing> for JsonValue {
     fn from(value: &Timing) -> Self {
         let mut map: HashMap<String, JsonValue> = HashMap::new();
@@ -133,6 +329,20 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        map.insert("part_1_alloc".into(), alloc_to_json(value.part_1_alloc));
+        map.insert("part_2_alloc".into(), alloc_to_json(value.part_2_alloc));
+
+        let part_1_error = value.part_1_error.clone().map(JsonValue::String);
+        let part_2_error = value.part_2_error.clone().map(JsonValue::String);
+        map.insert(
+            "part_1_error".into(),
+            part_1_error.unwrap_or(JsonValue::Null),
+        );
+        map.insert(
+            "part_2_error".into(),
+            part_2_error.unwrap_or(JsonValue::Null),
+        );
+
         JsonValue::Object(map)
     }
 }
@@ -166,15 +376,73 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        let part_1_alloc = alloc_from_json(json.get("part_1_alloc"))
+            .ok_or("Expected timing.part_1_alloc to be null or an object.")?;
+
+        let part_2_alloc = alloc_from_json(json.get("part_2_alloc"))
+            .ok_or("Expected timing.part_2_alloc to be null or an object.")?;
+
+        // NOTE: timings files written by older versions do not contain errors.
+        let part_1_error = json.get("part_1_error").and_then(|v| v.get::<String>());
+        let part_2_error = json.get("part_2_error").and_then(|v| v.get::<String>());
+
         Ok(Timing {
             day,
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
             total_nanos,
+            part_1_alloc,
+            part_2_alloc,
+            part_1_error: part_1_error.cloned(),
+            part_2_error: part_2_error.cloned(),
         })
     }
 }
 
+#[allow(clippy::cast_precision_loss)]
+fn alloc_to_json(value: Option<AllocStats>) -> JsonValue {
+    let Some(stats) = value else {
+        return JsonValue::Null;
+    };
+
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+    map.insert(
+        "peak_bytes".into(),
+        JsonValue::Number(stats.peak_bytes as f64),
+    );
+    map.insert(
+        "total_bytes".into(),
+        JsonValue::Number(stats.total_bytes as f64),
+    );
+    map.insert(
+        "allocations".into(),
+        JsonValue::Number(stats.allocations as f64),
+    );
+    JsonValue::Object(map)
+}
+
+/// Returns `Some(None)` for a missing or null value and `None` for a malformed one.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn alloc_from_json(value: Option<&JsonValue>) -> Option<Option<AllocStats>> {
+    // NOTE: timings files written by older versions do not contain allocation stats.
+    let Some(value) = value.filter(|v| !v.is_null()) else {
+        return Some(None);
+    };
+
+    let json = value.get::<HashMap<String, JsonValue>>()?;
+    let field = |key: &str| {
+        json.get(key)
+            .and_then(|v| v.get::<f64>())
+            .map(|v| *v as usize)
+    };
+
+    Some(Some(AllocStats {
+        peak_bytes: field("peak_bytes")?,
+        total_bytes: field("total_bytes")?,
+        allocations: field("allocations")?,
+    }))
+}
+
 /* -------------------------------------------------------------------------- */
 
 #[cfg(feature = "test_lib")]
@@ -191,20 +459,33 @@ mod tests {
                     part_1: Some("10ms".into()),
                     part_2: Some("20ms".into()),
                     total_nanos: 3e+10,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 },
                 Timing {
                     day: day!(2),
                     part_1: Some("30ms".into()),
                     part_2: Some("40ms".into()),
                     total_nanos: 7e+10,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 },
                 Timing {
                     day: day!(4),
                     part_1: Some("40ms".into()),
                     part_2: None,
                     total_nanos: 4e+10,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 },
             ],
+            ..Default::default()
         }
     }
 
@@ -230,6 +511,39 @@ mod tests {
             assert_eq!(timings.data.len(), 0);
         }
 
+        #[test]
+        fn handles_json_history() {
+            let json = r#"{ "data": [], "history": [{ "timestamp": 1700000000, "data": [{ "day": "03", "part_1": "2ms", "part_2": "3ms", "total_nanos": 5000000 }] }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            assert_eq!(timings.history.len(), 1);
+            let run = timings.history.first().unwrap();
+            assert_eq!(run.timestamp, 1_700_000_000);
+            assert_eq!(run.data.len(), 1);
+            assert_eq!(run.data[0].day, day!(3));
+        }
+
+        #[test]
+        fn handles_json_alloc_stats() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": null, "total_nanos": 1000000, "part_1_alloc": { "peak_bytes": 1024, "total_bytes": 2048, "allocations": 3 }, "part_2_alloc": null }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            let stats = timing.part_1_alloc.unwrap();
+            assert_eq!(stats.peak_bytes, 1024);
+            assert_eq!(stats.total_bytes, 2048);
+            assert_eq!(stats.allocations, 3);
+            assert_eq!(timing.part_2_alloc, None);
+            assert_eq!(timings.has_alloc_stats(), true);
+        }
+
+        #[test]
+        fn handles_json_errors() {
+            let json = r#"{ "data": [{ "day": "07", "part_1": "1ms", "part_2": null, "total_nanos": 1000000, "part_1_error": null, "part_2_error": "panicked: oh no" }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.part_1_error, None);
+            assert_eq!(timing.part_2_error, Some("panicked: oh no".into()));
+        }
+
         #[test]
         #[should_panic]
         fn panics_for_invalid_json() {
@@ -247,6 +561,7 @@ mod tests {
 
     mod serialization {
         use super::get_mock_timings;
+        use crate::template::timings::{Timings, MAX_HISTORY};
         use std::collections::HashMap;
         use tinyjson::JsonValue;
 
@@ -266,6 +581,42 @@ mod tests {
                 3
             );
         }
+
+        #[test]
+        fn roundtrips_history() {
+            let mut timings = get_mock_timings();
+            timings.push_history(timings.data.clone());
+            let json = JsonValue::from(timings).stringify().unwrap();
+            let parsed = Timings::try_from(json).unwrap();
+            assert_eq!(parsed.data.len(), 3);
+            assert_eq!(parsed.history.len(), 1);
+            assert_eq!(parsed.history[0].data.len(), 3);
+        }
+
+        #[test]
+        fn keeps_recent_history() {
+            let mut timings = get_mock_timings();
+            for _ in 0..MAX_HISTORY + 5 {
+                timings.push_history(timings.data.clone());
+            }
+            assert_eq!(timings.history.len(), MAX_HISTORY);
+
+            let merged = timings.merge(&timings);
+            assert_eq!(merged.history.len(), MAX_HISTORY);
+        }
+    }
+
+    mod parse_duration_nanos {
+        use crate::template::timings::parse_duration_nanos;
+
+        #[test]
+        fn parses_duration_units() {
+            assert_eq!(parse_duration_nanos("74.13ns"), Some(74.13));
+            assert_eq!(parse_duration_nanos("2µs"), Some(2000.0));
+            assert_eq!(parse_duration_nanos("1.5ms"), Some(1_500_000.0));
+            assert_eq!(parse_duration_nanos("2s"), Some(2_000_000_000.0));
+            assert_eq!(parse_duration_nanos("-"), None);
+        }
     }
 
     mod is_day_complete {
@@ -282,7 +633,12 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: Some("2ms".into()),
                     total_nanos: 3_000_000_000_f64,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 }],
+                ..Default::default()
             };
 
             assert_eq!(timings.is_day_complete(&day!(1)), true);
@@ -296,7 +652,12 @@ mod tests {
                     part_1: Some("1ms".into()),
                     part_2: None,
                     total_nanos: 1_000_000_000_f64,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 }],
+                ..Default::default()
             };
 
             assert_eq!(timings.is_day_complete(&day!(1)), false);
@@ -310,7 +671,12 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0.0,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 }],
+                ..Default::default()
             };
 
             assert_eq!(timings.is_day_complete(&day!(1)), false);
@@ -334,7 +700,12 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 }],
+                ..Default::default()
             };
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 4);
@@ -354,7 +725,12 @@ mod tests {
                     part_1: None,
                     part_2: None,
                     total_nanos: 0_f64,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: None,
                 }],
+                ..Default::default()
             };
             let merged = timings.merge(&other);
 
@@ -365,6 +741,33 @@ mod tests {
             assert_eq!(merged.data[2].day, day!(4));
         }
 
+        #[test]
+        fn keeps_stored_timings_of_failed_parts() {
+            let timings = get_mock_timings();
+
+            let other = Timings {
+                data: vec![Timing {
+                    day: day!(2),
+                    part_1: Some("35ms".into()),
+                    part_2: None,
+                    total_nanos: 35_000_000_f64,
+                    part_1_alloc: None,
+                    part_2_alloc: None,
+                    part_1_error: None,
+                    part_2_error: Some("timed out after 10s".into()),
+                }],
+                ..Default::default()
+            };
+            let merged = timings.merge(&other);
+
+            let timing = &merged.data[1];
+            assert_eq!(timing.day, day!(2));
+            assert_eq!(timing.part_1, Some("35ms".into()));
+            assert_eq!(timing.part_2, Some("40ms".into()));
+            assert_eq!(timing.part_2_error, None);
+            assert_eq!(timing.total_nanos, 75_000_000_f64);
+        }
+
         #[test]
         fn handles_empty_timings() {
             let timings = Timings::default();
@@ -381,4 +784,39 @@ mod tests {
             assert_eq!(merged.data.len(), 3);
         }
     }
+
+    mod benched_at {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        use crate::{
+            day,
+            template::timings::{Timings, TimingsRun},
+        };
+
+        #[test]
+        fn uses_latest_run_of_day() {
+            let mut timings = super::get_mock_timings();
+            let data = timings.data.clone();
+            timings.history = vec![
+                TimingsRun {
+                    timestamp: 100,
+                    data: data.clone(),
+                },
+                TimingsRun {
+                    timestamp: 200,
+                    data: data[..1].to_vec(),
+                },
+            ];
+
+            let at = |secs| Some(UNIX_EPOCH + Duration::from_secs(secs));
+            assert_eq!(timings.benched_at(day!(1)), at(200));
+            assert_eq!(timings.benched_at(day!(2)), at(100));
+        }
+
+        #[test]
+        fn handles_days_without_timings() {
+            let timings = Timings::default();
+            assert_eq!(timings.benched_at(day!(1)), None);
+        }
+    }
 }
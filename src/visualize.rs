@@ -0,0 +1,594 @@
+//! Lets solutions emit frames that `cargo solve <day> --visualize` renders as a terminal animation or exports as
+//! PPM / PNG images or SVG files.
+//!
+//! Solutions should emit frames through the [`visualize!`](crate::visualize!) macro. It expands to nothing unless
+//! the `visualize` feature is enabled, so the hooks cost nothing in regular runs and benchmarks.
+//!
+//! Coordinates follow the conventions of grids: `x` grows to the right and `y` grows downwards.
+use std::fmt::{Display, Write as _};
+use std::io::{self, stdout, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::{env, fs, thread};
+
+use crate::template::Day;
+
+/// Emits a frame if the `visualize` feature is enabled, and does nothing otherwise.
+/// Takes a call to one of the recording functions of this module.
+///
+/// ```
+/// # let (width, height) = (3, 3);
+/// use advent_of_code::visualize::Color;
+/// advent_of_code::visualize!(grid(width, height, |x, y| if x == y { Color::WHITE } else { Color::BLACK }));
+/// advent_of_code::visualize!(point(1, 2));
+/// ```
+#[macro_export]
+macro_rules! visualize {
+    ($($call:tt)*) => {
+        #[cfg(feature = "visualize")]
+        {
+            $crate::visualize::$($call)*;
+        }
+    };
+}
+
+/// Largest size of a path that is drawn in the terminal, in pixels. Two pixels are drawn per character.
+const TERMINAL_SIZE: (usize, usize) = (80, 80);
+/// Largest size of a path that is exported as an image, in pixels.
+const IMAGE_SIZE: (usize, usize) = (800, 800);
+/// Exported grids are scaled up until they are at least this large.
+const MIN_IMAGE_SIZE: usize = 400;
+/// Time the terminal animation takes if frames are not slowed down or sped up.
+const ANIMATION_DURATION: Duration = Duration::from_secs(5);
+
+static FRAMES: Mutex<Vec<Frame>> = Mutex::new(Vec::new());
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const WHITE: Color = Color(255, 255, 255);
+    pub const GRAY: Color = Color(128, 128, 128);
+    pub const RED: Color = Color(220, 50, 47);
+    pub const GREEN: Color = Color(133, 153, 0);
+    pub const BLUE: Color = Color(38, 139, 210);
+    pub const YELLOW: Color = Color(181, 137, 0);
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    /// A grid of colored cells, stored row by row.
+    Grid {
+        width: usize,
+        height: usize,
+        cells: Vec<Color>,
+    },
+    /// A path through a sequence of points.
+    Path(Vec<(i64, i64)>),
+}
+
+/// Records a grid frame, calling `color` for every cell.
+pub fn grid(width: usize, height: usize, color: impl Fn(usize, usize) -> Color) {
+    let cells = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| color(x, y))
+        .collect();
+
+    push(Frame::Grid {
+        width,
+        height,
+        cells,
+    });
+}
+
+/// Records a path frame.
+pub fn path(points: impl IntoIterator<Item = (i64, i64)>) {
+    push(Frame::Path(points.into_iter().collect()));
+}
+
+/// Extends the path recorded last by one point, or starts a new path if the last frame is not a path.
+pub fn point(x: i64, y: i64) {
+    let mut frames = FRAMES.lock().unwrap();
+    match frames.last_mut() {
+        Some(Frame::Path(points)) => points.push((x, y)),
+        _ => frames.push(Frame::Path(vec![(x, y)])),
+    }
+}
+
+fn push(frame: Frame) {
+    FRAMES.lock().unwrap().push(frame);
+}
+
+/// Removes and returns all frames recorded so far.
+pub fn take_frames() -> Vec<Frame> {
+    std::mem::take(&mut *FRAMES.lock().unwrap())
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Where frames are rendered to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    Terminal,
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "terminal" => Ok(Output::Terminal),
+            "ppm" => Ok(Output::Ppm),
+            "png" => Ok(Output::Png),
+            "svg" => Ok(Output::Svg),
+            _ => Err(format!(
+                "expecting one of `terminal`, `ppm`, `png` or `svg`, found `{s}`"
+            )),
+        }
+    }
+}
+
+impl Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Output::Terminal => "terminal",
+            Output::Ppm => "ppm",
+            Output::Png => "png",
+            Output::Svg => "svg",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Renders the frames recorded by a part, if the solution was started with `--visualize [<output>]`.
+/// Called by the runner once a part has finished.
+pub fn show(day: Day, part: u8) {
+    let args: Vec<String> = env::args().collect();
+    let Some(index) = args.iter().position(|x| x == "--visualize") else {
+        return;
+    };
+
+    let output = match args.get(index + 1).filter(|x| !x.starts_with("--")) {
+        Some(output) => match output.parse() {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("Invalid visualization output: {e}.");
+                return;
+            }
+        },
+        None => Output::Terminal,
+    };
+
+    let frames = take_frames();
+    if frames.is_empty() {
+        println!("Part {part} did not record any frames.");
+        return;
+    }
+
+    if output == Output::Terminal {
+        animate(&frames);
+        return;
+    }
+
+    let dir = crate::template::config::get()
+        .data_dir
+        .join("visualizations")
+        .join(format!("{day}-{part}"));
+
+    match export(&frames, output, &dir) {
+        Ok(count) => println!("Wrote {count} frame(s) to {}.", dir.display()),
+        Err(e) => eprintln!("Failed to write frames to {}: {e}", dir.display()),
+    }
+}
+
+/// Plays the frames as an animation, redrawing each frame over the previous one.
+fn animate(frames: &[Frame]) {
+    let delay = (ANIMATION_DURATION / u32::try_from(frames.len()).unwrap_or(u32::MAX))
+        .clamp(Duration::from_millis(10), Duration::from_millis(200));
+
+    let mut stdout = stdout();
+    let mut previous_lines = 0;
+
+    for frame in frames {
+        let text = terminal_frame(frame);
+
+        if previous_lines > 0 {
+            // move the cursor back up to overwrite the previous frame.
+            print!("\x1b[{previous_lines}A");
+        }
+
+        print!("{text}");
+        let _ = stdout.flush();
+
+        previous_lines = text.lines().count();
+        thread::sleep(delay);
+    }
+}
+
+/// Draws a frame with ANSI colors, using half blocks to fit two rows of pixels into a line.
+fn terminal_frame(frame: &Frame) -> String {
+    let (width, height, cells) = pixels(frame, TERMINAL_SIZE);
+    let cell = |x: usize, y: usize| {
+        if y < height {
+            cells[y * width + x]
+        } else {
+            Color::BLACK
+        }
+    };
+
+    let mut text = String::new();
+    for y in (0..height).step_by(2) {
+        for x in 0..width {
+            let (top, bottom) = (cell(x, y), cell(x, y + 1));
+            let _ = write!(
+                text,
+                "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀",
+                top.0, top.1, top.2, bottom.0, bottom.1, bottom.2
+            );
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// Writes every frame to a numbered file in `dir`, replacing frames of earlier runs.
+fn export(frames: &[Frame], output: Output, dir: &Path) -> Result<usize, io::Error> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+
+    let extension = match output {
+        Output::Terminal => unreachable!("terminal output is not exported"),
+        Output::Ppm => "ppm",
+        Output::Png => "png",
+        Output::Svg => "svg",
+    };
+
+    for (i, frame) in frames.iter().enumerate() {
+        let bytes = match output {
+            Output::Svg => svg(frame).into_bytes(),
+            _ => {
+                let (width, height, cells) = pixels(frame, IMAGE_SIZE);
+                let (width, height, cells) = scale_up(width, height, &cells);
+                if output == Output::Ppm {
+                    ppm(width, height, &cells)
+                } else {
+                    png(width, height, &cells)
+                }
+            }
+        };
+
+        fs::write(dir.join(format!("frame-{:04}.{extension}", i + 1)), bytes)?;
+    }
+
+    Ok(frames.len())
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Turns a frame into pixels. Paths are scaled down to fit into `max_size`.
+fn pixels(frame: &Frame, max_size: (usize, usize)) -> (usize, usize, Vec<Color>) {
+    match frame {
+        Frame::Grid {
+            width,
+            height,
+            cells,
+        } => (*width, *height, cells.clone()),
+        Frame::Path(points) => rasterize(points, max_size),
+    }
+}
+
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn rasterize(points: &[(i64, i64)], max_size: (usize, usize)) -> (usize, usize, Vec<Color>) {
+    let Some(bounds) = bounds(points) else {
+        return (0, 0, vec![]);
+    };
+    let (min_x, min_y, max_x, max_y) = bounds;
+
+    let span_x = (max_x - min_x) as f64 + 1.0;
+    let span_y = (max_y - min_y) as f64 + 1.0;
+    let scale = (max_size.0 as f64 / span_x)
+        .min(max_size.1 as f64 / span_y)
+        .min(1.0);
+
+    let width = ((span_x * scale).ceil() as usize).max(1);
+    let height = ((span_y * scale).ceil() as usize).max(1);
+    let project = |(x, y): (i64, i64)| {
+        (
+            (((x - min_x) as f64 * scale) as i64).min(width as i64 - 1),
+            (((y - min_y) as f64 * scale) as i64).min(height as i64 - 1),
+        )
+    };
+
+    let mut cells = vec![Color::BLACK; width * height];
+    let mut plot =
+        |(x, y): (i64, i64), color: Color| cells[y as usize * width + x as usize] = color;
+
+    let projected: Vec<_> = points.iter().copied().map(project).collect();
+    for segment in projected.windows(2) {
+        for pixel in line(segment[0], segment[1]) {
+            plot(pixel, Color::WHITE);
+        }
+    }
+
+    plot(projected[0], Color::GREEN);
+    if projected.len() > 1 {
+        plot(projected[projected.len() - 1], Color::RED);
+    }
+
+    (width, height, cells)
+}
+
+fn bounds(points: &[(i64, i64)]) -> Option<(i64, i64, i64, i64)> {
+    let min_x = points.iter().map(|p| p.0).min()?;
+    let min_y = points.iter().map(|p| p.1).min()?;
+    let max_x = points.iter().map(|p| p.0).max()?;
+    let max_y = points.iter().map(|p| p.1).max()?;
+    Some((min_x, min_y, max_x, max_y))
+}
+
+/// Pixels on the line between two points, using Bresenham's algorithm.
+fn line(from: (i64, i64), to: (i64, i64)) -> Vec<(i64, i64)> {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+
+    let mut pixels = vec![];
+    let (mut x, mut y) = from;
+    let mut error = dx + dy;
+
+    loop {
+        pixels.push((x, y));
+        if (x, y) == to {
+            return pixels;
+        }
+
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// Scales small grids up, so that exported images are not tiny.
+fn scale_up(width: usize, height: usize, cells: &[Color]) -> (usize, usize, Vec<Color>) {
+    let factor = (MIN_IMAGE_SIZE / width.max(height).max(1)).max(1);
+
+    let cells = (0..height * factor)
+        .flat_map(|y| (0..width * factor).map(move |x| (x / factor, y / factor)))
+        .map(|(x, y)| cells[y * width + x])
+        .collect();
+
+    (width * factor, height * factor, cells)
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn ppm(width: usize, height: usize, cells: &[Color]) -> Vec<u8> {
+    let mut bytes = format!("P6\n{width} {height}\n255\n").into_bytes();
+    bytes.extend(cells.iter().flat_map(|c| [c.0, c.1, c.2]));
+    bytes
+}
+
+/// Encodes an RGB image as PNG. The image data is stored without compression, which keeps the encoder tiny.
+fn png(width: usize, height: usize, cells: &[Color]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+    for row in cells.chunks(width.max(1)).take(height) {
+        // every row starts with its filter type, 0 means none.
+        raw.push(0);
+        raw.extend(row.iter().flat_map(|c| [c.0, c.1, c.2]));
+    }
+
+    let mut header = vec![];
+    header.extend(u32::try_from(width).unwrap().to_be_bytes());
+    header.extend(u32::try_from(height).unwrap().to_be_bytes());
+    // 8 bits per channel, truecolor, default compression, filtering and no interlacing.
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut bytes, b"IHDR", &header);
+    png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+    png_chunk(&mut bytes, b"IEND", &[]);
+    bytes
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend(u32::try_from(data.len()).unwrap().to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+/// Wraps data in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+
+    if blocks.peek().is_none() {
+        bytes.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let len = u16::try_from(block.len()).unwrap();
+        bytes.push(u8::from(blocks.peek().is_none()));
+        bytes.extend(len.to_le_bytes());
+        bytes.extend((!len).to_le_bytes());
+        bytes.extend(block);
+    }
+
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(u32::MAX, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1_u32, 0_u32), |(a, b), &byte| {
+        let a = (a + u32::from(byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+fn svg(frame: &Frame) -> String {
+    match frame {
+        Frame::Grid {
+            width,
+            height,
+            cells,
+        } => {
+            let mut rects = String::new();
+            for (i, color) in cells.iter().enumerate() {
+                let _ = writeln!(
+                    rects,
+                    r#"<rect x="{}" y="{}" width="1" height="1" fill="{}"/>"#,
+                    i % width,
+                    i / width,
+                    color.hex()
+                );
+            }
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">
+{rects}</svg>
+"#
+            )
+        }
+        Frame::Path(points) => {
+            let (min_x, min_y, max_x, max_y) = bounds(points).unwrap_or_default();
+            let coordinates = points
+                .iter()
+                .map(|(x, y)| format!("{x},{y}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let (width, height) = (max_x - min_x + 1, max_y - min_y + 1);
+            let stroke = width.max(height) as f64 / 400.0;
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{min_x} {min_y} {width} {height}">
+<rect x="{min_x}" y="{min_y}" width="{width}" height="{height}" fill="{}"/>
+<polyline points="{coordinates}" fill="none" stroke="{}" stroke-width="{stroke}" stroke-linejoin="round"/>
+</svg>
+"#,
+                Color::BLACK.hex(),
+                Color::WHITE.hex(),
+            )
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        crc32, grid, line, png, point, ppm, rasterize, svg, take_frames, Color, Frame, Output,
+    };
+
+    #[test]
+    fn records_frames() {
+        grid(2, 1, |x, _| if x == 0 { Color::WHITE } else { Color::RED });
+        point(0, 0);
+        point(3, 4);
+
+        assert_eq!(
+            take_frames(),
+            vec![
+                Frame::Grid {
+                    width: 2,
+                    height: 1,
+                    cells: vec![Color::WHITE, Color::RED]
+                },
+                Frame::Path(vec![(0, 0), (3, 4)])
+            ]
+        );
+        assert_eq!(take_frames(), vec![]);
+    }
+
+    #[test]
+    fn parses_outputs() {
+        assert_eq!("png".parse::<Output>(), Ok(Output::Png));
+        assert!("gif".parse::<Output>().is_err());
+    }
+
+    #[test]
+    fn draws_lines() {
+        assert_eq!(line((0, 0), (3, 1)), vec![(0, 0), (1, 0), (2, 1), (3, 1)]);
+        assert_eq!(line((2, 2), (2, 0)), vec![(2, 2), (2, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn rasterizes_paths() {
+        let (width, height, cells) = rasterize(&[(-1, 0), (1, 0), (1, 1)], (10, 10));
+        assert_eq!((width, height), (3, 2));
+        assert_eq!(
+            cells,
+            vec![
+                Color::GREEN,
+                Color::WHITE,
+                Color::WHITE,
+                Color::BLACK,
+                Color::BLACK,
+                Color::RED
+            ]
+        );
+    }
+
+    #[test]
+    fn scales_down_large_paths() {
+        let (width, height, _) = rasterize(&[(0, 0), (999, 499)], (100, 100));
+        assert_eq!((width, height), (100, 50));
+    }
+
+    #[test]
+    fn encodes_ppm() {
+        let bytes = ppm(1, 1, &[Color(1, 2, 3)]);
+        assert_eq!(bytes, b"P6\n1 1\n255\n\x01\x02\x03");
+    }
+
+    #[test]
+    fn encodes_png() {
+        let bytes = png(1, 1, &[Color::WHITE]);
+        assert!(bytes.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(bytes.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn draws_paths_as_svg() {
+        let svg = svg(&Frame::Path(vec![(0, 0), (2, -1)]));
+        assert!(svg.contains(r#"viewBox="0 -1 3 2""#));
+        assert!(svg.contains(r#"points="0,0 2,-1""#));
+    }
+}